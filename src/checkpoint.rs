@@ -0,0 +1,301 @@
+use super::types::{Env, build_directory_string, serialize_with_directory,
+deserialize_with_directory};
+
+use serde::{Serialize, Deserialize};
+use log::info;
+use std::fs::{create_dir_all, remove_dir_all, rename};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// how often a progress line is written
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct CheckpointConfig {
+    // write a checkpoint every n documents
+    pub interval: Option<usize>,
+    // continue from an existing checkpoint in the output directory
+    pub resume: bool
+}
+
+impl CheckpointConfig {
+    pub fn new() -> CheckpointConfig {
+        CheckpointConfig {
+            interval: Option::None,
+            resume: false
+        }
+    }
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> CheckpointConfig {
+        CheckpointConfig::new()
+    }
+}
+
+// state of an import which is persisted next to the partial env
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct ImportCheckpoint {
+    // files which are completely processed
+    pub files_done: Vec<String>,
+    // file which was processed while the checkpoint was written
+    pub current_file: Option<String>,
+    // documents already processed in current_file
+    pub current_file_docs: usize,
+    // documents processed over all files
+    pub count: usize
+}
+
+impl ImportCheckpoint {
+    pub const FILE_NAME: &'static str = "checkpoint.bin";
+    pub const DIR_NAME: &'static str = "checkpoint";
+
+    pub fn new() -> ImportCheckpoint {
+        ImportCheckpoint {
+            files_done: Vec::new(),
+            current_file: Option::None,
+            current_file_docs: 0,
+            count: 0
+        }
+    }
+
+    pub fn is_done(&self, file_name: &str) -> bool {
+        self.files_done.iter().any(|f| f == file_name)
+    }
+
+    // documents to skip when (re-)starting to process file_name
+    pub fn docs_to_skip(&self, file_name: &str) -> usize {
+        match &self.current_file {
+            Some(f) if f == file_name => self.current_file_docs,
+            _ => 0
+        }
+    }
+
+    pub fn start_file(&mut self, file_name: &str) {
+        if self.current_file.as_deref() != Some(file_name) {
+            self.current_file = Some(file_name.to_owned());
+            self.current_file_docs = 0;
+        }
+    }
+
+    pub fn finish_file(&mut self) {
+        if let Some(f) = self.current_file.take() {
+            self.files_done.push(f);
+        }
+        self.current_file_docs = 0;
+    }
+
+    pub fn checkpoint_dir(output_dir: &str) -> String {
+        build_directory_string(output_dir.to_owned(), ImportCheckpoint::DIR_NAME)
+    }
+
+    pub fn exists(output_dir: &str) -> bool {
+        Path::new(&build_directory_string(
+            ImportCheckpoint::checkpoint_dir(output_dir),
+            ImportCheckpoint::FILE_NAME)).exists()
+    }
+
+    // the env is written to a temporary directory first, which
+    // replaces the last checkpoint once it is complete
+    pub fn write(&self, env: &Env, output_dir: &str) {
+        let dir = ImportCheckpoint::checkpoint_dir(output_dir);
+        let tmp_dir = format!("{}.tmp", dir);
+
+        info!("writing checkpoint after {} documents.", self.count);
+
+        create_dir_all(&tmp_dir)
+            .unwrap_or_else(|_| panic!("Unable to create directory {}.", tmp_dir));
+
+        env.serialize(tmp_dir.clone());
+        serialize_with_directory(self, tmp_dir.clone(), ImportCheckpoint::FILE_NAME);
+
+        if Path::new(&dir).exists() {
+            remove_dir_all(&dir)
+                .unwrap_or_else(|_| panic!("Unable to remove directory {}.", dir));
+        }
+
+        rename(&tmp_dir, &dir)
+            .unwrap_or_else(|_| panic!("Unable to move {} to {}.", tmp_dir, dir));
+
+        info!("done writing checkpoint.");
+    }
+
+    pub fn read(output_dir: &str) -> (Env, ImportCheckpoint) {
+        let dir = ImportCheckpoint::checkpoint_dir(output_dir);
+
+        info!("resuming from checkpoint {}.", dir);
+
+        let env = Env::deserialize(dir.clone());
+        let checkpoint: ImportCheckpoint =
+            deserialize_with_directory(dir, ImportCheckpoint::FILE_NAME);
+
+        info!("{} documents, {} files already processed.",
+            checkpoint.count, checkpoint.files_done.len());
+
+        (env, checkpoint)
+    }
+
+    pub fn remove(output_dir: &str) {
+        let dir = ImportCheckpoint::checkpoint_dir(output_dir);
+        if Path::new(&dir).exists() {
+            info!("removing checkpoint {}.", dir);
+            remove_dir_all(&dir)
+                .unwrap_or_else(|_| panic!("Unable to remove directory {}.", dir));
+        }
+    }
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let s = d.as_secs();
+    format!("{:02}:{:02}:{:02}", s / 3600, (s / 60) % 60, s % 60)
+}
+
+pub struct ImportProgress {
+    total_bytes: u64,
+    // bytes of completely processed files
+    done_bytes: u64,
+    // bytes already processed when this run started
+    start_bytes: u64,
+    // documents processed in this run
+    docs: usize,
+    start: Instant,
+    last_report: Instant
+}
+
+impl ImportProgress {
+    pub fn new(total_bytes: u64) -> ImportProgress {
+        let now = Instant::now();
+        ImportProgress {
+            total_bytes,
+            done_bytes: 0,
+            start_bytes: 0,
+            docs: 0,
+            start: now,
+            last_report: now
+        }
+    }
+
+    // files skipped on resume do not count into the throughput
+    pub fn skip_file(&mut self, bytes: u64) {
+        self.done_bytes += bytes;
+        self.start_bytes += bytes;
+    }
+
+    pub fn finish_file(&mut self, bytes: u64) {
+        self.done_bytes += bytes;
+    }
+
+    // file_pos is the position of the reader in the current file
+    pub fn document_done(&mut self, file_pos: u64) {
+        self.docs += 1;
+
+        if self.last_report.elapsed() >= PROGRESS_INTERVAL {
+            self.last_report = Instant::now();
+            info!("{}", self.status(file_pos));
+        }
+    }
+
+    pub fn status(&self, file_pos: u64) -> String {
+        let elapsed = self.start.elapsed();
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+
+        let bytes = self.done_bytes + file_pos;
+        let bytes_per_sec = (bytes - self.start_bytes.min(bytes)) as f64 / secs;
+
+        let eta = if bytes_per_sec > 0.0 {
+            format_duration(Duration::from_secs_f64(
+                self.total_bytes.saturating_sub(bytes) as f64 / bytes_per_sec))
+        } else {
+            "unknown".to_owned()
+        };
+
+        format!("{:.1} of {:.1} MB ({:.1}%), {} documents, {:.1} documents/sec, elapsed {}, ETA {}",
+            bytes as f64 / 1_048_576.0,
+            self.total_bytes as f64 / 1_048_576.0,
+            if self.total_bytes == 0 { 100.0 }
+            else { 100.0 * bytes as f64 / self.total_bytes as f64 },
+            self.docs,
+            self.docs as f64 / secs,
+            format_duration(elapsed),
+            eta)
+    }
+}
+
+// bookkeeping of a running import, shared by the importers
+pub struct ImportRun<'a> {
+    pub checkpoint: ImportCheckpoint,
+    pub progress: ImportProgress,
+    config: &'a CheckpointConfig,
+    output_dir: &'a str
+}
+
+impl ImportRun<'_> {
+    // returns a fresh env or the env of the last checkpoint
+    // if resuming is configured
+    pub fn start<'a>(
+        files: &[String],
+        config: &'a CheckpointConfig,
+        output_dir: &'a str) -> (Env, ImportRun<'a>) {
+
+        let (env, checkpoint) = if config.resume && ImportCheckpoint::exists(output_dir) {
+            ImportCheckpoint::read(output_dir)
+        } else {
+            if config.resume {
+                info!("no checkpoint found in {}, starting from scratch.", output_dir);
+            }
+            (Env::new(), ImportCheckpoint::new())
+        };
+
+        let total_bytes = files.iter().map(|f| file_size(f)).sum();
+
+        (env, ImportRun {
+            checkpoint,
+            progress: ImportProgress::new(total_bytes),
+            config,
+            output_dir
+        })
+    }
+
+    pub fn limit_reached(&self, limit: Option<usize>) -> bool {
+        limit.is_some_and(|l| self.checkpoint.count >= l)
+    }
+
+    // returns false if the file was already processed by
+    // a former run, otherwise the file becomes the current file
+    pub fn start_file(&mut self, file_name: &str) -> bool {
+        if self.checkpoint.is_done(file_name) {
+            info!("skipping already processed file {}.", file_name);
+            self.progress.skip_file(file_size(file_name));
+            return false;
+        }
+        self.checkpoint.start_file(file_name);
+        true
+    }
+
+    pub fn finish_file(&mut self) {
+        if let Some(f) = &self.checkpoint.current_file {
+            self.progress.finish_file(file_size(f));
+        }
+        self.checkpoint.finish_file();
+    }
+
+    pub fn document_done(&mut self, env: &Env, file_pos: u64) {
+        self.checkpoint.count += 1;
+        self.checkpoint.current_file_docs += 1;
+        self.progress.document_done(file_pos);
+
+        if let Some(interval) = self.config.interval {
+            if interval > 0 && self.checkpoint.count.is_multiple_of(interval) {
+                self.checkpoint.write(env, self.output_dir);
+            }
+        }
+    }
+
+    pub fn finish(&self, file_pos: u64) {
+        info!("{}", self.progress.status(file_pos));
+        ImportCheckpoint::remove(self.output_dir);
+    }
+}
+
+fn file_size(file_name: &str) -> u64 {
+    std::fs::metadata(file_name).map(|m| m.len()).unwrap_or(0)
+}
@@ -0,0 +1,61 @@
+use super::*;
+use checkpoint::{format_duration, CheckpointConfig, ImportCheckpoint};
+use segmentation::Tokenizer;
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::time::Duration;
+
+#[test]
+fn test_format_duration(){
+    assert_eq!(format_duration(Duration::from_secs(0)), "00:00:00");
+    assert_eq!(format_duration(Duration::from_secs(3725)), "01:02:05");
+    assert_eq!(format_duration(Duration::from_secs(36000)), "10:00:00");
+}
+
+#[test]
+fn test_resume_from_checkpoint(){
+    let dir = std::env::temp_dir().join("rsrelex_checkpoint_test");
+    let input_dir = dir.join("input");
+    let output_dir = dir.join("output");
+    if dir.exists() {
+        remove_dir_all(&dir).unwrap();
+    }
+    create_dir_all(&input_dir).unwrap();
+    create_dir_all(&output_dir).unwrap();
+
+    write(input_dir.join("a.xml"), "<docs>\
+        <doc>Berlin is the capital of Germany.</doc>\
+        <doc>Paris is the capital of France.</doc>\
+        <doc>Rome is the capital of Italy.</doc>\
+        </docs>").unwrap();
+    write(input_dir.join("b.xml"), "<docs>\
+        <doc>Madrid is the capital of Spain.</doc>\
+        <doc>Lisbon is the capital of Portugal.</doc>\
+        </docs>").unwrap();
+
+    let input_dir = input_dir.to_str().unwrap();
+    let output_dir = output_dir.to_str().unwrap();
+    let files = xml::file_names_from_directory(input_dir).unwrap();
+
+    let (full_env, _) = xml::read_xmls_to_env(&files, b"doc", None, None,
+        &Tokenizer::Unicode, &CheckpointConfig::new(), output_dir);
+
+    // interrupted import, checkpoint written after every document
+    let config = CheckpointConfig { interval: Some(1), resume: false };
+    let (env, _) = xml::read_xmls_to_env(&files, b"doc", Some(4), None,
+        &Tokenizer::Unicode, &config, output_dir);
+    assert_eq!(env.sentences.sentences.len(), 4);
+    assert!(ImportCheckpoint::exists(output_dir));
+
+    let config = CheckpointConfig { interval: None, resume: true };
+    let (resumed_env, run) = xml::read_xmls_to_env(&files, b"doc", None, None,
+        &Tokenizer::Unicode, &config, output_dir);
+
+    assert_eq!(run.checkpoint.count, 5);
+    assert_eq!(resumed_env.sentences.sentences, full_env.sentences.sentences);
+    assert_eq!(resumed_env.dict.dict_vec, full_env.dict.dict_vec);
+
+    run.finish(0);
+    assert!(!ImportCheckpoint::exists(output_dir));
+
+    remove_dir_all(&dir).unwrap();
+}
@@ -1,11 +1,12 @@
-use super::types::{CoocInput, WordNr, CoocFst, CoocSnd, Env}; 
+use super::types::{CoocInput, WordNr, SentenceId, CoocFst, CoocSnd, Env,
+TermExplanation, CoocEvidence}; 
 
 use log::{debug, info, warn};
 use std::collections::HashMap;
 use std::collections::HashSet;
 use serde::{Serialize, Deserialize};
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
 pub struct ConexHyperParameter {
     // how many bootstrap words share this cooc
     cooc1_word_frequency_boost: f64 ,
@@ -173,6 +174,17 @@ pub fn do_conex_(
     bootstrap_set: &HashSet<WordNr>,
     hyper_params: &ConexHyperParameter, 
     env: &Env) -> Vec<WordNr> {
+
+    let cooc_fsts = cooc_fsts(bootstrap_set, hyper_params, env);
+
+    cooc_snds(&cooc_fsts, hyper_params, env).iter().map(|c| c.word).collect()
+}
+
+// surviving syntagmatic context of the bootstrap set
+fn cooc_fsts(
+    bootstrap_set: &HashSet<WordNr>,
+    hyper_params: &ConexHyperParameter, 
+    env: &Env) -> Vec<CoocFst> {
     // this can get seriously wrong if the numbers outgrow
     // i16::MIN, but if this happens our fitness score
     // is messed up anyways
@@ -246,6 +258,16 @@ pub fn do_conex_(
     debug!("{} from {} syntagmatic coocs left after applying threshold of {}",
         cooc_fsts.len(), l1, hyper_params.cooc1_survivor_threshold); 
 
+    cooc_fsts
+}
+
+// surviving paradigmatic context, the co-occurrences of the
+// syntagmatic context
+fn cooc_snds(
+    cooc_fsts: &[CoocFst],
+    hyper_params: &ConexHyperParameter, 
+    env: &Env) -> Vec<CoocSnd> {
+
     // cooc_fsts.sort_unstable_by(
     //     |a, b| 
     //     a.fitness.cmp(&b.fitness));
@@ -256,7 +278,7 @@ pub fn do_conex_(
     let mut coocs_on_cooc_snd: HashMap<WordNr, CoocSnd> = HashMap::new(); 
 
     debug!("Collecting paradigmatic context");
-    for cooc in cooc_fsts {
+    for cooc in cooc_fsts.iter() {
         let coocs_for_word = cooccurrences_for_word(cooc.word, env);
         let mut already_cooc_frequency_boosted: HashSet<WordNr> = HashSet::new(); 
 
@@ -305,5 +327,97 @@ pub fn do_conex_(
     // debug!("{:?}", cooc_snds.iter().map(|c| 
     //         (env.dict.get_word(&c.word), c.fitness)).collect::<Vec<(&str, f64)>>());
 
-    cooc_snds.iter().map(|c| c.word).collect()
+    cooc_snds
+}
+
+// the seed words and first-order co-occurrences which made term a
+// result of do_conex, with sample sentences containing both the
+// co-occurrence and the term. None if term is no result.
+pub fn explain_term(
+    cooc_input: &CoocInput,
+    term: &str,
+    hyper_params: &ConexHyperParameter, 
+    env: &Env,
+    max_sentences: usize) -> Option<TermExplanation> {
+
+    let term_nr = env.dict.get_opt_nr(term)?;
+    let bootstrap_set = cooc_input_to_word_nr_set(cooc_input, env);
+    let cooc_fsts = cooc_fsts(&bootstrap_set, hyper_params, env);
+    let cooc_snd = cooc_snds(&cooc_fsts, hyper_params, env).into_iter()
+        .find(|c| c.word == term_nr)?;
+
+    let term_sentences = env.get_inverted_idx(&term_nr);
+
+    let mut coocs: Vec<CoocEvidence> = cooc_fsts.iter()
+        .filter_map(|cooc| {
+            let cooc_sentences = env.get_inverted_idx(&cooc.word);
+            let mut s_ids: Vec<SentenceId> = cooc_sentences
+                .intersection(term_sentences)
+                .copied()
+                .collect();
+            if s_ids.is_empty() {
+                return None;
+            }
+            s_ids.sort_unstable();
+
+            let mut seeds: Vec<String> = bootstrap_set.iter()
+                .filter(|seed| !env.get_inverted_idx(seed).is_disjoint(cooc_sentences))
+                .map(|seed| env.dict.get_word(seed).to_owned())
+                .collect();
+            seeds.sort_unstable();
+
+            Some(CoocEvidence {
+                word: env.dict.get_word(&cooc.word).to_owned(),
+                fitness: cooc.fitness,
+                seeds,
+                sentence_count: s_ids.len(),
+                sentences: s_ids.iter()
+                    .take(max_sentences)
+                    .map(|s_id| env.get_argument(env.get_sentence(s_id)))
+                    .collect()
+            })
+        })
+        .collect();
+    coocs.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness)
+        .unwrap_or(std::cmp::Ordering::Equal)
+        .then_with(|| a.word.cmp(&b.word)));
+
+    let mut seeds: Vec<String> = coocs.iter()
+        .flat_map(|c| c.seeds.iter().cloned())
+        .collect();
+    seeds.sort_unstable();
+    seeds.dedup();
+
+    Some(TermExplanation {
+        term: term.to_owned(),
+        fitness: cooc_snd.fitness,
+        seeds,
+        coocs
+    })
+}
+
+// keeps the words whose most frequent part-of-speech tag is one of tags
+pub fn filter_by_pos(words: &[WordNr], tags: &[String], env: &Env) -> Vec<WordNr> {
+
+    if !env.has_pos() {
+        warn!("No part-of-speech annotations loaded, not filtering by {:?}.", tags);
+        return words.to_vec();
+    }
+
+    let tags: HashSet<WordNr> = tags.iter()
+        .filter_map(|t| {
+            let tag = env.pos.dict.get_opt_nr(t);
+            if tag.is_none() {
+                warn!("Part-of-speech tag \"{}\" not found in corpus.", t);
+            }
+            tag
+        })
+        .collect();
+
+    words.iter()
+        .filter(|w| env.get_pos_counts(w).iter()
+            .max_by_key(|(_, count)| *count)
+            .is_some_and(|(tag, _)| tags.contains(tag)))
+        .copied()
+        .collect()
 }
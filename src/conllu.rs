@@ -0,0 +1,247 @@
+use super::types::{Env, Document, SentenceId, soundness_test};
+use super::checkpoint::{CheckpointConfig, ImportRun};
+use super::xml::file_names_from_directory;
+
+use log::{info, warn};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ConlluSentence {
+    pub forms: Vec<String>,
+    pub lemmas: Vec<String>,
+    pub upos: Vec<String>
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ConlluDocument {
+    // from "# newdoc id = ...", empty if not given
+    pub id: String,
+    pub sentences: Vec<ConlluSentence>
+}
+
+// reads documents from a CoNLL-U file. Documents are delimited by
+// "# newdoc" comments, a file without them is a single document.
+pub struct ConlluReader<B: BufRead> {
+    lines: std::io::Lines<B>,
+    // bytes read so far
+    position: usize,
+    // id of the next document, set by the last "# newdoc"
+    next_id: Option<String>,
+    done: bool
+}
+
+impl<B: BufRead> ConlluReader<B> {
+    pub fn new(reader: B) -> ConlluReader<B> {
+        ConlluReader {
+            lines: reader.lines(),
+            position: 0,
+            next_id: Option::None,
+            done: false
+        }
+    }
+
+    pub fn buffer_position(&self) -> usize {
+        self.position
+    }
+}
+
+fn newdoc_id(comment: &str) -> Option<String> {
+    let c = comment.trim_start_matches('#').trim();
+    if !c.starts_with("newdoc") {
+        return Option::None;
+    }
+    Some(c.split_once('=').map(|(_, id)| id.trim().to_owned()).unwrap_or_default())
+}
+
+impl<B: BufRead> Iterator for ConlluReader<B> {
+    type Item = ConlluDocument;
+
+    fn next(&mut self) -> Option<ConlluDocument> {
+        if self.done {
+            return Option::None;
+        }
+
+        let mut doc = ConlluDocument {
+            id: self.next_id.take().unwrap_or_default(),
+            sentences: Vec::new()
+        };
+        let mut sent = ConlluSentence::default();
+
+        loop {
+            let line = match self.lines.next() {
+                None => {
+                    self.done = true;
+                    break;
+                }
+                Some(l) => l.expect("Error while reading CoNLL-U file.")
+            };
+            self.position += line.len() + 1;
+
+            let line = line.trim_end();
+
+            if line.is_empty() {
+                if !sent.forms.is_empty() {
+                    doc.sentences.push(std::mem::take(&mut sent));
+                }
+                continue;
+            }
+
+            if line.starts_with('#') {
+                if let Some(id) = newdoc_id(line) {
+                    if !doc.sentences.is_empty() {
+                        self.next_id = Some(id);
+                        return Some(doc);
+                    }
+                    doc.id = id;
+                }
+                continue;
+            }
+
+            let cols: Vec<&str> = line.split('\t').collect();
+            if cols.len() != 10 {
+                warn!("skipping malformed CoNLL-U line \"{}\".", line);
+                continue;
+            }
+
+            // multiword token ranges (1-2) and empty nodes (1.1)
+            // are not part of the word sequence
+            if cols[0].contains('-') || cols[0].contains('.') {
+                continue;
+            }
+
+            sent.forms.push(cols[1].to_owned());
+            sent.lemmas.push(
+                if cols[2] == "_" { cols[1] } else { cols[2] }.to_owned());
+            sent.upos.push(cols[3].to_owned());
+        }
+
+        if !sent.forms.is_empty() {
+            doc.sentences.push(sent);
+        }
+
+        if doc.sentences.is_empty() { Option::None } else { Some(doc) }
+    }
+}
+
+pub fn add_conllu_document_to_env(doc: &ConlluDocument, id: &str, env: &mut Env) {
+    let first_sentence = env.sentences.sentences.len() as SentenceId;
+
+    fn as_strs(v: &[String]) -> Vec<&str> {
+        v.iter().map(|s| s.as_str()).collect()
+    }
+
+    for sent in &doc.sentences {
+        env.add_sentence(&as_strs(&sent.forms));
+        env.lemmas.add_sentence(&as_strs(&sent.lemmas));
+        env.pos.add_sentence(&as_strs(&sent.upos));
+    }
+
+    env.documents.documents.push(Document {
+        id: id.to_owned(),
+        title: String::new(),
+        first_sentence,
+        sentence_count: env.sentences.sentences.len() as u32 - first_sentence
+    });
+}
+
+pub fn read_conllu_and_persist_env(
+    input_dir: &str,
+    output_dir: &str,
+    limit: Option<usize>,
+    checkpointing: &CheckpointConfig) {
+
+    info!("starting read_conllu_and_persist_env.");
+    info!("reading files from directory {}.", input_dir);
+
+    let files: Vec<String> = file_names_from_directory(input_dir)
+        .expect("Unable to read file names from directory {}!")
+        .into_iter()
+        .filter(|f| f.ends_with(".conllu") || f.ends_with(".conll"))
+        .collect();
+
+    let (env, run) = read_conllus_to_env(&files, limit, checkpointing, output_dir);
+
+    info!("done reading files from directory.");
+
+    info!("{} sentences loaded, with {} distinct words, {} lemmas and {} pos tags.",
+        env.sentences.sentences.len(),
+        env.dict.dict_vec.len(),
+        env.lemmas.dict.dict_vec.len(),
+        env.pos.dict.dict_vec.len());
+
+    info!("Starting soundness test.");
+    soundness_test(&env);
+    info!("Done soundness test.");
+
+    info!("starting writing binary files.");
+    env.serialize(output_dir.to_owned());
+    info!("done writing binary files.");
+
+    run.finish(0);
+
+    info!("done read_conllu_and_persist_env.");
+}
+
+pub fn read_conllus_to_env<'a>(
+    files: &[String],
+    limit: Option<usize>,
+    checkpointing: &'a CheckpointConfig,
+    output_dir: &'a str) -> (Env, ImportRun<'a>) {
+
+    let (mut env, mut run) = ImportRun::start(files, checkpointing, output_dir);
+
+    for file_name in files {
+        if run.limit_reached(limit) { break; }
+
+        if ! run.start_file(file_name) { continue; }
+
+        if process_conllu_file(file_name, &mut env, limit, &mut run) {
+            run.finish_file();
+        }
+    }
+
+    (env, run)
+}
+
+// returns true if the file was read to the end
+fn process_conllu_file(
+    file_name: &str,
+    env: &mut Env,
+    limit: Option<usize>,
+    run: &mut ImportRun) -> bool {
+
+    let f = File::open(file_name)
+        .unwrap_or_else(|_| panic!("Unable to open file {}.", file_name));
+
+    let mut docs = ConlluReader::new(BufReader::new(f));
+
+    // documents processed by a former run are skipped
+    let skip = run.checkpoint.docs_to_skip(file_name);
+
+    info!("Start reading file {}", file_name);
+
+    let mut file_count = 0usize;
+    while let Some(doc) = docs.next() {
+        file_count += 1;
+        if file_count <= skip { continue; }
+
+        if run.limit_reached(limit) {
+            info!("document limit reached.");
+            return false;
+        }
+
+        let id = if doc.id.is_empty() {
+            format!("{}#{}", file_name, file_count)
+        } else {
+            doc.id.clone()
+        };
+
+        add_conllu_document_to_env(&doc, &id, env);
+
+        run.document_done(env, docs.buffer_position() as u64);
+    }
+
+    info!("done reading file.");
+    true
+}
@@ -0,0 +1,109 @@
+use super::*;
+use conllu::{ConlluReader, ConlluDocument, add_conllu_document_to_env};
+
+const CONLLU: &str = "# newdoc id = doc1
+# sent_id = 1
+# text = Berlin is the capital of Germany.
+1\tBerlin\tBerlin\tPROPN\tNNP\t_\t4\tnsubj\t_\t_
+2\tis\tbe\tAUX\tVBZ\t_\t4\tcop\t_\t_
+3\tthe\tthe\tDET\tDT\t_\t4\tdet\t_\t_
+4\tcapital\tcapital\tNOUN\tNN\t_\t0\troot\t_\t_
+5\tof\tof\tADP\tIN\t_\t6\tcase\t_\t_
+6\tGermany\tGermany\tPROPN\tNNP\t_\t4\tnmod\t_\tSpaceAfter=No
+7\t.\t.\tPUNCT\t.\t_\t4\tpunct\t_\t_
+
+# newdoc id = doc2
+# sent_id = 2
+1-2\tdel\t_\t_\t_\t_\t_\t_\t_\t_
+1\tde\tde\tADP\t_\t_\t3\tcase\t_\t_
+2\tel\tel\tDET\t_\t_\t3\tdet\t_\t_
+3\tcapital\t_\tNOUN\t_\t_\t0\troot\t_\t_
+3.1\tis\tbe\tAUX\t_\t_\t_\t_\t3:cop\t_
+
+";
+
+#[test]
+fn test_conllu_reader(){
+    let docs: Vec<ConlluDocument> = ConlluReader::new(CONLLU.as_bytes()).collect();
+
+    assert_eq!(docs.len(), 2);
+    assert_eq!(docs[0].id, "doc1");
+    assert_eq!(docs[0].sentences.len(), 1);
+    assert_eq!(docs[0].sentences[0].forms,
+        vec!["Berlin", "is", "the", "capital", "of", "Germany", "."]);
+    assert_eq!(docs[0].sentences[0].lemmas[1], "be");
+
+    // multiword tokens and empty nodes are skipped, missing lemma is the form
+    assert_eq!(docs[1].id, "doc2");
+    assert_eq!(docs[1].sentences[0].forms, vec!["de", "el", "capital"]);
+    assert_eq!(docs[1].sentences[0].lemmas, vec!["de", "el", "capital"]);
+    assert_eq!(docs[1].sentences[0].upos, vec!["ADP", "DET", "NOUN"]);
+}
+
+#[test]
+fn test_conllu_annotation_layers(){
+    let mut env = Env::new();
+    for (i, doc) in ConlluReader::new(CONLLU.as_bytes()).enumerate() {
+        add_conllu_document_to_env(&doc, &i.to_string(), &mut env);
+    }
+
+    assert_eq!(env.sentences.sentences.len(), 2);
+    assert_eq!(env.pos.sentences.len(), 2);
+    assert_eq!(env.lemmas.sentences[0].len(), env.sentences.sentences[0].len());
+
+    let is = env.dict.get_nr("is");
+    assert_eq!(env.lemmas.dict.get_word(&env.lemmas.get(&0, 1).unwrap()), "be");
+    assert_eq!(env.get_document(&1).unwrap().id, "1");
+
+    let propn = env.pos.dict.get_nr("PROPN");
+    let berlin = env.dict.get_nr("Berlin");
+    assert_eq!(env.get_pos_counts(&berlin).get(&propn), Some(&1));
+
+    assert_eq!(conex::filter_by_pos(&[berlin, is],
+        &["PROPN".to_owned(), "NOUN".to_owned()], &env), vec![berlin]);
+}
+
+#[test]
+fn test_relex_pos_slot(){
+    // id, form and upos of every token, the other columns are empty
+    let conllu: String = [
+        "Berlin PROPN|is AUX|the DET|capital NOUN|of ADP|Germany PROPN|. PUNCT",
+        "Paris PROPN|is AUX|the DET|capital NOUN|of ADP|France PROPN|. PUNCT",
+        "Madrid PROPN|is AUX|the DET|seat NOUN|of ADP|Spain PROPN|. PUNCT",
+        "Oslo PROPN|is AUX|the DET|largest ADJ|of ADP|Norway PROPN|. PUNCT"]
+        .iter()
+        .map(|sent| sent.split('|').enumerate()
+            .map(|(i, token)| {
+                let (form, upos) = token.split_once(' ').unwrap();
+                format!("{}\t{}\t_\t{}\t_\t_\t_\t_\t_\t_\n", i + 1, form, upos)
+            })
+            .collect::<String>() + "\n")
+        .collect();
+
+    let mut env = Env::new();
+    for (i, doc) in ConlluReader::new(conllu.as_bytes()).enumerate() {
+        add_conllu_document_to_env(&doc, &i.to_string(), &mut env);
+    }
+
+    let mut di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+    di.options.pos_generalise = vec!("NOUN".to_owned());
+
+    // a single pattern, so a single pattern suffices for a pair
+    let hyper_params = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 10.0,
+        ..relex::RelexHyperParameter::default()
+    };
+
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &hyper_params, &env, types::DefaultLogger::new()));
+
+    assert_eq!(result.patterns.len(), 1);
+    assert_eq!(result.patterns[0].infix, vec!("is", "the", "<NOUN>", "of"));
+
+    // "seat" is a noun like "capital", "largest" is not
+    let pairs: Vec<(&str, &str)> = result.pairs.iter()
+        .map(|p| (p.w1.as_str(), p.w2.as_str()))
+        .collect();
+    assert!(pairs.contains(&("Spain", "Madrid")));
+    assert!(!pairs.iter().any(|(w1, _)| *w1 == "Norway"));
+}
@@ -0,0 +1,238 @@
+use super::types::{RelationResult, ScoredPattern};
+
+use serde::{Serialize, Deserialize};
+
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+const OWL: &str = "http://www.w3.org/2002/07/owl#";
+
+// export of relex results as a knowledge graph
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct GraphOptions {
+    // IRI prefix of the arguments
+    pub entity_prefix: String,
+    // IRI prefix of the relation names (predicates)
+    pub relation_prefix: String,
+    // IRI prefix of the confidence and pattern properties of the
+    // reified statements
+    pub metadata_prefix: String,
+    // predicate of a single relation, coupled relations use their names
+    pub relation: String
+}
+
+impl Default for GraphOptions {
+    fn default() -> GraphOptions {
+        GraphOptions {
+            entity_prefix: "http://example.org/entity/".to_owned(),
+            relation_prefix: "http://example.org/relation/".to_owned(),
+            metadata_prefix: "http://example.org/relex#".to_owned(),
+            relation: "related_to".to_owned()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphFormat {
+    NTriples,
+    Turtle,
+    // edge list, one pair per line
+    Csv
+}
+
+impl GraphFormat {
+    pub fn parse(format: &str) -> Option<GraphFormat> {
+        match format {
+            "ntriples" | "nt" => Some(GraphFormat::NTriples),
+            "turtle" | "ttl" => Some(GraphFormat::Turtle),
+            "csv" => Some(GraphFormat::Csv),
+            _ => None
+        }
+    }
+}
+
+// a term of a statement, Iri(prefix, local name)
+enum Term {
+    Iri(String, String),
+    Literal(String, Option<String>),
+    Blank(usize)
+}
+
+// all words of an argument or relation name in one IRI segment,
+// spaces become underscores, everything else which is neither
+// alphanumeric nor an underscore is percent encoded, so the segment
+// is a valid local name of a turtle prefixed name as well
+pub fn iri_segment(name: &str) -> String {
+    let mut segment = String::new();
+    for c in name.chars() {
+        if c == ' ' {
+            segment.push('_');
+        } else if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for b in c.encode_utf8(&mut buf).bytes() {
+                segment.push_str(&format!("%{:02X}", b));
+            }
+        }
+    }
+    segment
+}
+
+fn escape_literal(literal: &str) -> String {
+    let mut escaped = String::new();
+    for c in literal.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c)
+        }
+    }
+    escaped
+}
+
+fn pattern_string(pattern: &ScoredPattern) -> String {
+    pattern.infix.join(" ")
+}
+
+// statements of every pair: the fact itself and its reification with
+// the confidence (fitness) and the patterns which found the pair
+fn statements(results: &[RelationResult], options: &GraphOptions) -> Vec<(Term, Term, Term)> {
+    let entity = |w: &str| Term::Iri(options.entity_prefix.clone(), iri_segment(w));
+    let rdf = |name: &str| Term::Iri(RDF.to_owned(), name.to_owned());
+    let meta = |name: &str| Term::Iri(options.metadata_prefix.clone(), name.to_owned());
+
+    let mut statements = Vec::new();
+    let mut blank = 0;
+
+    for RelationResult { name, result } in results {
+        let relation = || Term::Iri(options.relation_prefix.clone(), iri_segment(name));
+
+        if result.symmetric {
+            statements.push((relation(), rdf("type"),
+                Term::Iri(OWL.to_owned(), "SymmetricProperty".to_owned())));
+        }
+
+        for pair in &result.pairs {
+            statements.push((entity(&pair.w1), relation(), entity(&pair.w2)));
+
+            let statement = || Term::Blank(blank);
+            statements.push((statement(), rdf("type"), rdf("Statement")));
+            statements.push((statement(), rdf("subject"), entity(&pair.w1)));
+            statements.push((statement(), rdf("predicate"), relation()));
+            statements.push((statement(), rdf("object"), entity(&pair.w2)));
+            statements.push((statement(), meta("confidence"),
+                Term::Literal(pair.fitness.to_string(), Some("double".to_owned()))));
+            for p in &pair.patterns {
+                statements.push((statement(), meta("pattern"),
+                    Term::Literal(pattern_string(&result.patterns[*p]), None)));
+            }
+            blank += 1;
+        }
+    }
+
+    statements
+}
+
+fn ntriples_term(term: &Term) -> String {
+    match term {
+        Term::Iri(prefix, local) => format!("<{}{}>", prefix, local),
+        Term::Literal(value, None) => format!("\"{}\"", escape_literal(value)),
+        Term::Literal(value, Some(datatype)) =>
+            format!("\"{}\"^^<{}{}>", escape_literal(value), XSD, datatype),
+        Term::Blank(n) => format!("_:s{}", n)
+    }
+}
+
+// prefixed names for the prefixes declared by to_turtle
+fn turtle_term(term: &Term, prefixes: &[(&str, &str)]) -> String {
+    match term {
+        Term::Iri(prefix, local) => match prefixes.iter().find(|(_, iri)| iri == prefix) {
+            Some((name, _)) => format!("{}:{}", name, local),
+            None => ntriples_term(term)
+        },
+        Term::Literal(value, Some(datatype)) =>
+            format!("\"{}\"^^xsd:{}", escape_literal(value), datatype),
+        _ => ntriples_term(term)
+    }
+}
+
+fn to_turtle(statements: &[(Term, Term, Term)], options: &GraphOptions) -> String {
+    let prefixes = [
+        ("e", options.entity_prefix.as_str()),
+        ("r", options.relation_prefix.as_str()),
+        ("rx", options.metadata_prefix.as_str()),
+        ("rdf", RDF),
+        ("xsd", XSD),
+        ("owl", OWL)
+    ];
+
+    let mut turtle: String = prefixes.iter()
+        .map(|(name, iri)| format!("@prefix {}: <{}> .\n", name, iri))
+        .collect();
+
+    // statements sharing a subject are grouped with ";"
+    let mut last_subject = String::new();
+    for (s, p, o) in statements {
+        let subject = turtle_term(s, &prefixes);
+        if subject == last_subject {
+            turtle.push_str(&format!(" ;\n    {} {}", turtle_term(p, &prefixes),
+                turtle_term(o, &prefixes)));
+        } else {
+            if !last_subject.is_empty() {
+                turtle.push_str(" .\n");
+            }
+            turtle.push_str(&format!("{} {} {}", subject, turtle_term(p, &prefixes),
+                turtle_term(o, &prefixes)));
+            last_subject = subject;
+        }
+    }
+    if !last_subject.is_empty() {
+        turtle.push_str(" .\n");
+    }
+
+    turtle
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+// source, target, relation, confidence and the patterns separated by |
+fn to_csv(results: &[RelationResult]) -> String {
+    let mut csv = "source,target,relation,confidence,patterns\n".to_owned();
+    for RelationResult { name, result } in results {
+        for pair in &result.pairs {
+            let patterns = pair.patterns.iter()
+                .map(|p| pattern_string(&result.patterns[*p]))
+                .collect::<Vec<String>>()
+                .join("|");
+
+            csv.push_str(&[csv_field(&pair.w1), csv_field(&pair.w2), csv_field(name),
+                pair.fitness.to_string(), csv_field(&patterns)].join(","));
+            csv.push('\n');
+        }
+    }
+    csv
+}
+
+// the pairs of all results as a graph, the relation name of each
+// result is the predicate
+pub fn to_graph(results: &[RelationResult], options: &GraphOptions, format: GraphFormat)
+    -> String {
+
+    match format {
+        GraphFormat::NTriples => statements(results, options).iter()
+            .map(|(s, p, o)| format!("{} {} {} .\n",
+                ntriples_term(s), ntriples_term(p), ntriples_term(o)))
+            .collect(),
+        GraphFormat::Turtle => to_turtle(&statements(results, options), options),
+        GraphFormat::Csv => to_csv(results)
+    }
+}
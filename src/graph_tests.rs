@@ -0,0 +1,80 @@
+use super::*;
+use graph::{GraphFormat, GraphOptions, iri_segment, to_graph};
+use types::{RelationResult, RelexResult, ScoredPair, ScoredPattern};
+
+fn capital_results() -> Vec<RelationResult> {
+    let pattern = ScoredPattern {
+        prefix: None,
+        infix: vec!("is".to_owned(), "the".to_owned(), "capital".to_owned(), "of".to_owned()),
+        suffix: None,
+        order: true,
+        fitness: 24.0,
+        scope: String::new()
+    };
+    let pair = |w1: &str, w2: &str, fitness| ScoredPair {
+        w1: w1.to_owned(),
+        w2: w2.to_owned(),
+        fitness,
+        patterns: vec!(0)
+    };
+
+    vec!(RelationResult {
+        name: "capital of".to_owned(),
+        result: RelexResult {
+            patterns: vec!(pattern),
+            pairs: vec!(pair("Berlin", "Germany", 30.5), pair("Washington, D.C.", "USA", 20.0)),
+            symmetric: false
+        }
+    })
+}
+
+#[test]
+fn test_iri_segment() {
+    assert_eq!(iri_segment("New York"), "New_York");
+    assert_eq!(iri_segment("Washington, D.C."), "Washington%2C_D%2EC%2E");
+    assert_eq!(iri_segment("Zürich"), "Zürich");
+    assert_eq!(iri_segment("\"x\""), "%22x%22");
+}
+
+#[test]
+fn test_graph_ntriples() {
+    let nt = to_graph(&capital_results(), &GraphOptions::default(), GraphFormat::NTriples);
+    let lines: Vec<&str> = nt.lines().collect();
+
+    assert_eq!(lines[0], "<http://example.org/entity/Berlin> \
+        <http://example.org/relation/capital_of> <http://example.org/entity/Germany> .");
+    assert!(lines.contains(&"_:s0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#subject> \
+        <http://example.org/entity/Berlin> ."));
+    assert!(lines.contains(&"_:s0 <http://example.org/relex#confidence> \
+        \"30.5\"^^<http://www.w3.org/2001/XMLSchema#double> ."));
+    assert!(lines.contains(&"_:s1 <http://example.org/relex#pattern> \"is the capital of\" ."));
+    // fact, type, subject, predicate, object, confidence and pattern per pair
+    assert_eq!(lines.len(), 14);
+}
+
+#[test]
+fn test_graph_turtle() {
+    let mut results = capital_results();
+    results[0].result.symmetric = true;
+    let options = GraphOptions {
+        entity_prefix: "http://kg.test/e/".to_owned(),
+        ..Default::default()
+    };
+
+    let ttl = to_graph(&results, &options, GraphFormat::Turtle);
+
+    assert!(ttl.starts_with("@prefix e: <http://kg.test/e/> .\n"));
+    assert!(ttl.contains("r:capital_of rdf:type owl:SymmetricProperty .\n"));
+    assert!(ttl.contains("e:Berlin r:capital_of e:Germany .\n"));
+    assert!(ttl.contains("_:s0 rdf:type rdf:Statement ;\n    rdf:subject e:Berlin ;\n"));
+    assert!(ttl.contains("    rx:confidence \"30.5\"^^xsd:double ;\n"));
+}
+
+#[test]
+fn test_graph_csv() {
+    let csv = to_graph(&capital_results(), &GraphOptions::default(), GraphFormat::Csv);
+
+    assert_eq!(csv, "source,target,relation,confidence,patterns\n\
+        Berlin,Germany,capital of,30.5,is the capital of\n\
+        \"Washington, D.C.\",USA,capital of,20,is the capital of\n");
+}
@@ -0,0 +1,295 @@
+use super::types::{WordNr, SentenceId, Env, RelexOptions, HearstResult, HypernymPair,
+is_concrete_word};
+use super::relex::{ArgumentSpans, match_infix};
+use super::segmentation::Tokenizer;
+
+use log::{info, warn};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+// words separating the items of a list slot, at most two of them
+// between two items (", and"). The last item of a list of several
+// items follows a conjunction, unless the template continues with
+// one ("Y+ and other X").
+const LIST_SEPARATORS: [&str; 3] = [",", "and", "or"];
+const LIST_CONJUNCTIONS: [&str; 2] = ["and", "or"];
+
+// lexico-syntactic patterns for hypernyms after Hearst (1992)
+pub const DEFAULT_HEARST_TEMPLATES: [&str; 8] = [
+    "X such as Y+",
+    "X , such as Y+",
+    "such X as Y+",
+    "Y+ and other X",
+    "Y+ or other X",
+    "X including Y+",
+    "X , including Y+",
+    "X , especially Y+"
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Slot {
+    Hypernym,
+    Hyponym
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Element {
+    // literal words, matched like a pattern infix
+    Words(Vec<WordNr>),
+    // an argument, or a list of arguments
+    Slot(Slot, bool)
+}
+
+// a template like "X such as Y+": X is the hypernym slot, Y the
+// hyponym slot, a + makes a slot a list of arguments ("Y, Z and W").
+// Everything else is matched like the infix of a pattern, so "*",
+// "<TAG>" and "*{0,n}" may be used as well.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    elements: Vec<Element>
+}
+
+impl Template {
+    // None if a word of the template is unknown (it can't match) or
+    // the template lacks a slot or a concrete word
+    pub fn parse(template: &str, env: &Env) -> Option<Template> {
+        let mut elements: Vec<Element> = Vec::new();
+
+        let push_word = |elements: &mut Vec<Element>, w: WordNr| {
+            match elements.last_mut() {
+                Some(Element::Words(words)) => words.push(w),
+                _ => elements.push(Element::Words(vec!(w)))
+            }
+        };
+
+        for token in template.split_whitespace() {
+            // pattern syntax is kept as is
+            if token.starts_with('*') || token.starts_with('<') {
+                push_word(&mut elements, env.get_infix_word_nr(token)?);
+                continue;
+            }
+
+            for word in Tokenizer::Unicode.tokenize(token) {
+                match (word, elements.last_mut()) {
+                    ("X", _) => elements.push(Element::Slot(Slot::Hypernym, false)),
+                    ("Y", _) => elements.push(Element::Slot(Slot::Hyponym, false)),
+                    ("+", Some(Element::Slot(_, list))) => *list = true,
+                    _ => push_word(&mut elements, env.dict.get_opt_nr(word)?)
+                }
+            }
+        }
+
+        let has_slot = |slot| elements.iter()
+            .any(|e| matches!(e, Element::Slot(s, _) if *s == slot));
+        let has_word = elements.iter().any(|e| matches!(e, Element::Words(words)
+            if words.iter().any(|w| is_concrete_word(*w))));
+
+        if has_slot(Slot::Hypernym) && has_slot(Slot::Hyponym) && has_word {
+            Some(Template { elements })
+        } else {
+            None
+        }
+    }
+
+    fn concrete_words(&self) -> impl Iterator<Item = &WordNr> {
+        self.elements.iter()
+            .flat_map(|e| match e {
+                Element::Words(words) => words.as_slice(),
+                _ => &[]
+            })
+            .filter(|w| is_concrete_word(**w))
+    }
+}
+
+struct Matcher<'a> {
+    sent: &'a [WordNr],
+    s_id: SentenceId,
+    spans: &'a ArgumentSpans,
+    separators: &'a HashSet<WordNr>,
+    conjunctions: &'a HashSet<WordNr>,
+    env: &'a Env
+}
+
+impl<'a> Matcher<'a> {
+    // an argument starting at pos, separators and punctuation are none
+    fn item(&self, pos: usize) -> Option<(usize, usize)> {
+        let (start, end) = self.spans.argument(self.sent, &self.s_id, pos, false, self.env)?;
+        let is_word = |w: &WordNr| !self.separators.contains(w)
+            && self.env.dict.get_word(w).starts_with(char::is_alphanumeric);
+
+        Some((start, end)).filter(|_| self.sent[start..end].iter().all(is_word))
+    }
+
+    // ranges of the longest list of items starting at pos, each with
+    // whether a conjunction precedes it
+    fn list(&self, pos: usize) -> Vec<((usize, usize), bool)> {
+        let mut items = Vec::new();
+        let mut next = Some(pos);
+        let mut conjunction = false;
+
+        while let Some(range) = next.and_then(|pos| self.item(pos)) {
+            items.push((range, conjunction));
+            next = (1..=2)
+                .map(|n| range.1 + n)
+                .take_while(|pos| *pos <= self.sent.len()
+                    && self.separators.contains(&self.sent[pos - 1]))
+                .last();
+            conjunction = next.is_some_and(|next| self.sent[range.1..next].iter()
+                .any(|w| self.conjunctions.contains(w)));
+        }
+
+        items
+    }
+
+    // matches the elements at pos, the bindings of the slots are
+    // collected. Lists are tried from the longest to the shortest.
+    fn match_elements(&self, elements: &[Element], pos: usize,
+        bindings: &mut Vec<(Slot, (usize, usize))>) -> bool {
+
+        let (element, rest) = match elements.split_first() {
+            Some(x) => x,
+            None => return true
+        };
+
+        match element {
+            Element::Words(words) => match_infix(words, self.sent, pos, &self.s_id, self.env)
+                .is_some_and(|end| self.match_elements(rest, end, bindings)),
+
+            Element::Slot(slot, false) => match self.item(pos) {
+                Some(range) => {
+                    bindings.push((*slot, range));
+                    let matched = self.match_elements(rest, range.1, bindings);
+                    if !matched {
+                        bindings.pop();
+                    }
+                    matched
+                },
+                None => false
+            },
+
+            Element::Slot(slot, true) => {
+                let items = self.list(pos);
+                let n = bindings.len();
+                let conjunction_follows = matches!(rest.first(),
+                    Some(Element::Words(words)) if self.conjunctions.contains(&words[0]));
+
+                for k in (1..=items.len()).rev() {
+                    if k > 1 && !items[k - 1].1 && !conjunction_follows {
+                        continue;
+                    }
+                    bindings.extend(items[..k].iter().map(|(range, _)| (*slot, *range)));
+                    if self.match_elements(rest, items[k - 1].0.1, bindings) {
+                        return true;
+                    }
+                    bindings.truncate(n);
+                }
+                false
+            }
+        }
+    }
+}
+
+type HypernymKey = (Vec<WordNr>, Vec<WordNr>);
+
+// (hypernym, hyponym) pairs of every match of the template
+fn match_template(template: &Template, spans: &ArgumentSpans,
+    separators: &HashSet<WordNr>, conjunctions: &HashSet<WordNr>, env: &Env)
+    -> Vec<(SentenceId, HypernymKey)> {
+
+    // only sentences containing every concrete word of the template
+    let mut concrete = template.concrete_words();
+    let mut sentence_ids: HashSet<SentenceId> = match concrete.next() {
+        Some(w) => env.get_inverted_idx(w).to_owned(),
+        None => return Vec::new()
+    };
+    for w in concrete {
+        sentence_ids = sentence_ids
+            .intersection(env.get_inverted_idx(w))
+            .copied()
+            .collect();
+    }
+
+    let leading_slot = matches!(template.elements.first(), Some(Element::Slot(..)));
+
+    let mut pairs = Vec::new();
+    for s_id in sentence_ids {
+        let sent = env.get_sentence(&s_id);
+        let matcher = Matcher { sent, s_id, spans, separators, conjunctions, env };
+
+        for pos in 0..sent.len() {
+            // a leading argument has to start at pos, not within an
+            // argument of several words
+            if leading_slot && pos > 0 && spans.is_argument_word(sent, &s_id, pos - 1, env) {
+                continue;
+            }
+
+            let mut bindings = Vec::new();
+            if !matcher.match_elements(&template.elements, pos, &mut bindings) {
+                continue;
+            }
+
+            let arguments = |slot| bindings.iter()
+                .filter(move |(s, _)| *s == slot)
+                .map(|(_, (start, end))| sent[*start..*end].to_vec());
+
+            for hypernym in arguments(Slot::Hypernym) {
+                for hyponym in arguments(Slot::Hyponym).filter(|h| *h != hypernym) {
+                    pairs.push((s_id, (hypernym.clone(), hyponym)));
+                }
+            }
+        }
+    }
+
+    pairs
+}
+
+// matches the templates over all sentences, arguments are found like
+// the arguments of relex (see RelexOptions.arguments). The count of a
+// pair is the count of sentences per template it was found in.
+pub fn do_hearst(templates: &[String], options: &RelexOptions, env: &Env) -> HearstResult {
+    let spans = ArgumentSpans::new(options, env);
+    let words = |words: &[&str]| words.iter()
+        .filter_map(|w| env.dict.get_opt_nr(w))
+        .collect::<HashSet<WordNr>>();
+    let separators = words(&LIST_SEPARATORS);
+    let conjunctions = words(&LIST_CONJUNCTIONS);
+
+    let mut found: HashMap<HypernymKey, (usize, BTreeSet<usize>)> = HashMap::new();
+
+    for (i, t) in templates.iter().enumerate() {
+        let template = match Template::parse(t, env) {
+            Some(template) => template,
+            None => {
+                warn!("Template \"{}\" has unknown words or misses X, Y or a word \
+                    - skipping it.", t);
+                continue;
+            }
+        };
+
+        let matches: HashSet<(SentenceId, HypernymKey)> =
+            match_template(&template, &spans, &separators, &conjunctions, env).into_iter().collect();
+        info!("Template \"{}\" found {} pairs.", t, matches.len());
+
+        for (_, key) in matches {
+            let (count, found_by) = found.entry(key).or_insert((0, BTreeSet::new()));
+            *count += 1;
+            found_by.insert(i);
+        }
+    }
+
+    let mut pairs: Vec<HypernymPair> = found.into_iter()
+        .map(|((hypernym, hyponym), (count, found_by))| HypernymPair {
+            hypernym: env.get_argument(&hypernym),
+            hyponym: env.get_argument(&hyponym),
+            count,
+            templates: found_by.into_iter().collect()
+        })
+        .collect();
+    pairs.sort_by(|a, b| b.count.cmp(&a.count)
+        .then_with(|| a.hypernym.cmp(&b.hypernym))
+        .then_with(|| a.hyponym.cmp(&b.hyponym)));
+
+    HearstResult {
+        templates: templates.to_vec(),
+        pairs
+    }
+}
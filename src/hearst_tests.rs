@@ -0,0 +1,66 @@
+use super::*;
+use hearst::{Template, do_hearst, DEFAULT_HEARST_TEMPLATES};
+use segmentation::Tokenizer;
+
+fn fruit_env() -> Env {
+    let mut env = Env::new();
+    xml::add_text_to_env("We sell fruits such as apples, pears and plums. \
+        Bananas, cherries and other fruits are imported. \
+        They grow such vegetables as carrots. \
+        Many fruits, including apples, are sweet. \
+        The market sells apples and pears.", &mut env, &Tokenizer::Unicode);
+    env
+}
+
+fn pairs(result: &types::HearstResult) -> Vec<(&str, &str, usize)> {
+    result.pairs.iter()
+        .map(|p| (p.hypernym.as_str(), p.hyponym.as_str(), p.count))
+        .collect()
+}
+
+#[test]
+fn test_hearst_template_parse() {
+    let env = fruit_env();
+
+    assert!(Template::parse("X such as Y+", &env).is_some());
+    assert!(Template::parse("X , including Y", &env).is_some());
+    // unknown words can't match
+    assert!(Template::parse("X like Y+", &env).is_none());
+    // the hyponym slot is missing
+    assert!(Template::parse("X such as", &env).is_none());
+    // slots only
+    assert!(Template::parse("X Y", &env).is_none());
+    // gaps are limited like in pattern files
+    assert!(Template::parse("X such as *{0,2} Y+", &env).is_some());
+    assert!(Template::parse("X such as *{0,300} Y+", &env).is_none());
+}
+
+#[test]
+fn test_hearst_lists() {
+    let env = fruit_env();
+    let templates: Vec<String> = DEFAULT_HEARST_TEMPLATES.iter()
+        .map(|t| t.to_string())
+        .collect();
+
+    let result = do_hearst(&templates, &types::RelexOptions::default(), &env);
+    let pairs = pairs(&result);
+
+    // found by "X such as Y+" and "X , including Y+"
+    assert_eq!(pairs[0], ("fruits", "apples", 2));
+    assert!(pairs.contains(&("fruits", "pears", 1)));
+    assert!(pairs.contains(&("fruits", "plums", 1)));
+    assert!(pairs.contains(&("fruits", "Bananas", 1)));
+    assert!(pairs.contains(&("fruits", "cherries", 1)));
+    assert!(pairs.contains(&("vegetables", "carrots", 1)));
+    // the list ends before "and other", a list without a conjunction
+    // ends after its first item ("apples, are sweet")
+    assert!(!pairs.iter().any(|(_, hyponym, _)| *hyponym == "other" || *hyponym == "are"));
+    assert_eq!(pairs.len(), 6);
+
+    let apples = &result.pairs[0];
+    let found_by: Vec<&str> = apples.templates.iter()
+        .map(|t| result.templates[*t].as_str())
+        .collect();
+    assert_eq!(found_by, vec!("X such as Y+", "X , including Y+"));
+}
+
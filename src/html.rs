@@ -0,0 +1,203 @@
+use super::types::{Env, soundness_test};
+use super::segmentation::Tokenizer;
+use super::checkpoint::{CheckpointConfig, ImportRun};
+use super::xml::{add_document_to_env, file_names_from_directory};
+
+use log::{info, warn};
+use regex::Regex;
+use std::fs::read;
+
+// blocks with less words are considered boilerplate
+// (menus, bylines, copyright notes, ...)
+const MIN_BLOCK_WORDS: usize = 8;
+
+// blocks where more than this fraction of the text
+// is link text are considered navigation
+const MAX_LINK_DENSITY: f64 = 0.5;
+
+lazy_static! {
+    // elements which never contain main text
+    static ref REMOVE: Vec<Regex> = vec!(
+        Regex::new(r"(?is)<!--.*?-->").unwrap(),
+        Regex::new(r"(?is)<script\b[^>]*>.*?</script\s*>").unwrap(),
+        Regex::new(r"(?is)<style\b[^>]*>.*?</style\s*>").unwrap(),
+        Regex::new(r"(?is)<noscript\b[^>]*>.*?</noscript\s*>").unwrap(),
+        Regex::new(r"(?is)<head\b[^>]*>.*?</head\s*>").unwrap(),
+        Regex::new(r"(?is)<nav\b[^>]*>.*?</nav\s*>").unwrap(),
+        Regex::new(r"(?is)<header\b[^>]*>.*?</header\s*>").unwrap(),
+        Regex::new(r"(?is)<footer\b[^>]*>.*?</footer\s*>").unwrap(),
+        Regex::new(r"(?is)<aside\b[^>]*>.*?</aside\s*>").unwrap(),
+        Regex::new(r"(?is)<form\b[^>]*>.*?</form\s*>").unwrap(),
+        Regex::new(r"(?is)<iframe\b[^>]*>.*?</iframe\s*>").unwrap(),
+        Regex::new(r"(?is)<svg\b[^>]*>.*?</svg\s*>").unwrap(),
+    );
+
+    static ref TITLE: Regex = Regex::new(r"(?is)<title\b[^>]*>(.*?)</title\s*>").unwrap();
+
+    // if present, only the content of <main> or <article> is used
+    static ref MAIN: Regex = Regex::new(r"(?is)<main\b[^>]*>(.*)</main\s*>").unwrap();
+    static ref ARTICLE: Regex = Regex::new(r"(?is)<article\b[^>]*>(.*)</article\s*>").unwrap();
+
+    static ref BLOCK: Regex = Regex::new(
+        r"(?i)</?(p|div|br|li|ul|ol|dl|dt|dd|h[1-6]|table|tr|td|th|section|article|main|blockquote|pre|hr|figure|figcaption)\b[^>]*>").unwrap();
+
+    static ref LINK: Regex = Regex::new(r"(?is)<a\b[^>]*>(.*?)</a\s*>").unwrap();
+    static ref TAG: Regex = Regex::new(r"(?s)<[^>]*>").unwrap();
+    static ref WHITESPACE: Regex = Regex::new(r"\s+").unwrap();
+    static ref NUMERIC_ENTITY: Regex = Regex::new(r"&#(x[0-9a-fA-F]+|[0-9]+);").unwrap();
+
+    static ref ENTITIES: Vec<(&'static str, &'static str)> = vec!(
+        ("&nbsp;", " "),
+        ("&quot;", "\""),
+        ("&apos;", "'"),
+        ("&lt;", "<"),
+        ("&gt;", ">"),
+        ("&ndash;", "–"),
+        ("&mdash;", "—"),
+        ("&hellip;", "…"),
+        ("&amp;", "&")
+    );
+}
+
+pub fn decode_entities(s: &str) -> String {
+    let mut s: String = NUMERIC_ENTITY.replace_all(s, |c: &regex::Captures| {
+        let n = &c[1];
+        let code = if let Some(hex) = n.strip_prefix('x') {
+            u32::from_str_radix(hex, 16).ok()
+        } else {
+            n.parse().ok()
+        };
+        code.and_then(std::char::from_u32)
+            .map(|c| c.to_string())
+            .unwrap_or_default()
+    }).into();
+
+    for (search, replace) in ENTITIES.iter() {
+        s = s.replace(search, replace);
+    }
+    s
+}
+
+fn strip_tags(s: &str) -> String {
+    let s = TAG.replace_all(s, "");
+    let s = decode_entities(&s);
+    WHITESPACE.replace_all(&s, " ").trim().to_owned()
+}
+
+pub fn extract_title(html: &str) -> String {
+    TITLE.captures(html)
+        .map(|c| strip_tags(&c[1]))
+        .unwrap_or_default()
+}
+
+// main text as one paragraph per line
+pub fn extract_main_text(html: &str) -> String {
+
+    let mut s: String = html.into();
+
+    for regex in REMOVE.iter() {
+        s = regex.replace_all(&s, " ").into();
+    }
+
+    if let Some(c) = MAIN.captures(&s).or_else(|| ARTICLE.captures(&s)) {
+        s = c[1].to_owned();
+    }
+
+    BLOCK.split(&s)
+        .filter_map(|block| {
+            let text = strip_tags(block);
+            if text.split_whitespace().count() < MIN_BLOCK_WORDS {
+                return Option::None;
+            }
+
+            let link_len: usize = LINK.captures_iter(block)
+                .map(|c| strip_tags(&c[1]).len())
+                .sum();
+            if link_len as f64 / text.len() as f64 > MAX_LINK_DENSITY {
+                return Option::None;
+            }
+
+            Some(text)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn is_html_file(file_name: &str) -> bool {
+    let f = file_name.to_lowercase();
+    f.ends_with(".html") || f.ends_with(".htm") || f.ends_with(".xhtml")
+}
+
+pub fn read_html_and_persist_env(
+    input_dir: &str,
+    output_dir: &str,
+    limit: Option<usize>,
+    tokenizer: &Tokenizer,
+    checkpointing: &CheckpointConfig) {
+
+    info!("starting read_html_and_persist_env.");
+    info!("reading files from directory {}.", input_dir);
+
+    let files: Vec<String> = file_names_from_directory(input_dir)
+        .expect("Unable to read file names from directory {}!")
+        .into_iter()
+        .filter(|f| is_html_file(f))
+        .collect();
+
+    info!("{} html files found.", files.len());
+
+    let (env, run) = read_htmls_to_env(&files, limit, tokenizer,
+        checkpointing, output_dir);
+
+    info!("done reading files from directory.");
+
+    info!("{} sentences loaded, with {} distinct words."
+             , env.sentences.sentences.len(), env.dict.dict_vec.len());
+
+    info!("Starting soundness test.");
+    soundness_test(&env);
+    info!("Done soundness test.");
+
+    info!("starting writing binary files.");
+    env.serialize(output_dir.to_owned());
+    info!("done writing binary files.");
+
+    run.finish(0);
+
+    info!("done read_html_and_persist_env.");
+}
+
+pub fn read_htmls_to_env<'a>(
+    files: &[String],
+    limit: Option<usize>,
+    tokenizer: &Tokenizer,
+    checkpointing: &'a CheckpointConfig,
+    output_dir: &'a str) -> (Env, ImportRun<'a>) {
+
+    let (mut env, mut run) = ImportRun::start(files, checkpointing, output_dir);
+
+    for file_name in files {
+        if run.limit_reached(limit) { break; }
+
+        if ! run.start_file(file_name) { continue; }
+
+        // crawled pages are not necessarily valid utf-8
+        let bytes = read(file_name)
+            .unwrap_or_else(|_| panic!("Unable to read file {}.", file_name));
+        let html = String::from_utf8_lossy(&bytes);
+
+        let title = extract_title(&html);
+        let text = extract_main_text(&html);
+
+        if text.is_empty() {
+            warn!("no main text found in {}.", file_name);
+        }
+
+        add_document_to_env(file_name, &title, &text, &mut env, tokenizer);
+
+        run.document_done(&env, bytes.len() as u64);
+        run.finish_file();
+    }
+
+    (env, run)
+}
@@ -0,0 +1,42 @@
+use super::*;
+
+const PAGE: &str = "<html>
+<head><title>Berlin &ndash; Capital</title>
+<script>var x = \"<p>not text</p>\";</script></head>
+<body>
+<nav><ul><li><a href=\"/\">Home</a></li><li><a href=\"/news\">News</a></li></ul></nav>
+<div class=\"menu\"><a href=\"/a\">Politics and the economy</a> | <a href=\"/b\">Culture and sports of the region</a> | more</div>
+<p>Berlin is the capital and largest city of <b>Germany</b> by both area and population.</p>
+<p>Share this</p>
+<p>The city is also one of the states of Germany&#44; and is the most populous city.</p>
+<footer><p>Copyright 2020, all rights reserved by the very long footer company.</p></footer>
+</body>
+</html>";
+
+#[test]
+fn test_extract_title(){
+    assert_eq!(html::extract_title(PAGE), "Berlin – Capital");
+    assert_eq!(html::extract_title("<p>no title</p>"), "");
+}
+
+#[test]
+fn test_extract_main_text(){
+    assert_eq!(html::extract_main_text(PAGE),
+        "Berlin is the capital and largest city of Germany by both area and population.\n\
+        The city is also one of the states of Germany, and is the most populous city.");
+}
+
+#[test]
+fn test_extract_main_text_article(){
+    let page = "<body><p>Some long teaser text which is outside of the article.</p>\
+        <article><h1>Title</h1><p>Only the text within the article element is used here.</p></article>\
+        </body>";
+    assert_eq!(html::extract_main_text(page),
+        "Only the text within the article element is used here.");
+}
+
+#[test]
+fn test_decode_entities(){
+    assert_eq!(html::decode_entities("a&amp;b &lt;c&gt; &#x41;&#66;&nbsp;"), "a&b <c> AB ");
+    assert_eq!(html::decode_entities("&amp;lt;"), "&lt;");
+}
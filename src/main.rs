@@ -1,9 +1,17 @@
 pub mod service;
 pub mod types;
 pub mod relex;
+pub mod nary;
+pub mod graph;
+pub mod hearst;
 pub mod conex;
 pub mod wikitext;
 pub mod xml;
+pub mod html;
+pub mod medline;
+pub mod conllu;
+pub mod segmentation;
+pub mod checkpoint;
 pub mod pso;
 pub mod mopso;
 pub mod pso_train;
@@ -25,17 +33,34 @@ mod pso_tests;
 mod pso_train_tests;
 #[cfg(test)]
 mod utils_tests;
+#[cfg(test)]
+mod segmentation_tests;
+#[cfg(test)]
+mod checkpoint_tests;
+#[cfg(test)]
+mod html_tests;
+#[cfg(test)]
+mod medline_tests;
+#[cfg(test)]
+mod conllu_tests;
+#[cfg(test)]
+mod nary_tests;
+#[cfg(test)]
+mod graph_tests;
+#[cfg(test)]
+mod hearst_tests;
 
 use serde::{Serialize, Deserialize};
 use toml;
-use log::{info, error};
+use log::{info, warn, error};
 use types::{WordNr, soundness_test, Env};
 use xml::{read_xml_and_persist_env, PreprocessorFunction};
+use segmentation::{Tokenizer, MaxMatchSegmenter};
+use checkpoint::CheckpointConfig;
 use std::env;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs::read_to_string;
-use std::io::{self, BufRead};
 use getopts::{Matches, Options};
 use rand::seq::SliceRandom;
 use atty::Stream;
@@ -78,20 +103,7 @@ fn run_xml_import(
         Some(d) => { d }
     };
 
-    if ! matches.opt_present("xt") {
-        error!("If x option present xt (xml-tag) needs to be present as well!");
-        print_usage(&program, opts);
-        return;
-    }
-
-    let tag = match matches.opt_str("xt") {
-        None => {
-            error!("If x option present xt (xml-tag) needs to be present as well!");
-            print_usage(&program, opts);
-            return;
-        }
-        Some(t) => { t }
-    };
+    let format = matches.opt_str("xf").unwrap_or_else(|| "xml".to_owned());
 
     let limit: Option<usize> = if matches.opt_present("xl") {
         matches.opt_str("xl").and_then(|l| l.parse().ok()) 
@@ -119,12 +131,89 @@ fn run_xml_import(
         Option::None
     };
 
-    read_xml_and_persist_env(
-        &input_dir, 
-        &bin_file_dir, 
-        &tag.as_bytes(), 
-        limit, 
-        preprocessor);
+    let tokenizer = match matches.opt_str("xs").as_deref() {
+        None | Some("unicode") => {
+            info!("using unicode word segmentation.");
+            Tokenizer::Unicode
+        }
+        Some("maxmatch") => {
+            let lexicon_file = match matches.opt_str("xlex") {
+                None => {
+                    error!("Segmenter maxmatch needs a lexicon (--xlex)!");
+                    print_usage(program, opts);
+                    return;
+                }
+                Some(f) => { f }
+            };
+            info!("using maxmatch segmentation with lexicon {}.", lexicon_file);
+            Tokenizer::MaxMatch(MaxMatchSegmenter::from_file(&lexicon_file))
+        }
+        Some(s) => {
+            error!("Unknown segmenter {}!", s);
+            print_usage(program, opts);
+            return;
+        }
+    };
+
+    let checkpointing = CheckpointConfig {
+        interval: matches.opt_str("xc").and_then(|c| c.parse().ok()),
+        resume: matches.opt_present("xr")
+    };
+
+    match format.as_str() {
+        "xml" => {
+            if ! matches.opt_present("xt") {
+                error!("If x option present xt (xml-tag) needs to be present as well!");
+                print_usage(program, opts);
+                return;
+            }
+
+            let tag = match matches.opt_str("xt") {
+                None => {
+                    error!("If x option present xt (xml-tag) needs to be present as well!");
+                    print_usage(program, opts);
+                    return;
+                }
+                Some(t) => { t }
+            };
+
+            read_xml_and_persist_env(
+                &input_dir, 
+                &bin_file_dir, 
+                tag.as_bytes(), 
+                limit, 
+                preprocessor,
+                &tokenizer,
+                &checkpointing);
+        }
+        "html" => {
+            html::read_html_and_persist_env(
+                &input_dir,
+                &bin_file_dir,
+                limit,
+                &tokenizer,
+                &checkpointing);
+        }
+        "medline" => {
+            medline::read_medline_and_persist_env(
+                &input_dir,
+                &bin_file_dir,
+                limit,
+                &tokenizer,
+                &checkpointing);
+        }
+        "conllu" => {
+            conllu::read_conllu_and_persist_env(
+                &input_dir,
+                &bin_file_dir,
+                limit,
+                &checkpointing);
+        }
+        f => {
+            error!("Unknown import format {}!", f);
+            print_usage(program, opts);
+        }
+    }
 }
 
 fn run_training(
@@ -166,6 +255,12 @@ fn run_training(
             .and_then(|l| l.parse().ok()).unwrap_or(5)
     } else { 5 };
 
+    if matches.opt_present("trelex") {
+        run_relex_training(matches, &reference_file, &outfile, 
+            do_mopso, num_particles, iterations, nbwords, env);
+        return;
+    }
+
     let reference_words = utils::read_word_file(&reference_file, &env); 
 
     // check if run from a pipe
@@ -238,20 +333,142 @@ fn run_training(
     // info!("Position: {:?}, Fitness: {:?}", p, f);
 }
 
-fn run_server(){
+#[allow(clippy::too_many_arguments)]
+fn run_relex_training(
+    matches: &Matches,
+    reference_file: &str,
+    outfile: &str,
+    do_mopso: bool,
+    num_particles: usize,
+    iterations: usize,
+    nbpairs: usize,
+    env: &Env
+    ){
+
+    // relex options are taken from the config, the
+    // hyper parameters and seed pairs are not used
+    let options = match matches.opt_str("trelex") {
+        None => types::RelexOptions::default(),
+        Some(config_file) => {
+            let config: RelexConfig = 
+                toml::from_str(&read_to_string(&config_file)
+                .unwrap_or_else(|_| panic!("Unable to open file \"{}\".", &config_file)))
+                .unwrap_or_else(|_| panic!("Unable to read file \"{}\".", &config_file));
+            config.options
+        }
+    };
+
+    let reference_pairs = utils::read_pair_file(reference_file, env); 
+
+    let pair_str = |pairs: &[types::ArgumentPair]| pairs.iter()
+        .map(|(w1, w2)| (env.get_argument(w1), env.get_argument(w2)))
+        .collect::<Vec<(String, String)>>();
+
+    // check if run from a pipe
+    let seed_pairs: Vec<types::ArgumentPair> = 
+        // if no pipe, randomize tnbwords pairs from the reference pairs
+        if atty::is(Stream::Stdin) {
+
+            let mut rng = rand::thread_rng();
+
+            let seed_pairs: Vec<types::ArgumentPair> = reference_pairs
+                .choose_multiple(&mut rng, nbpairs)
+                .cloned().collect();
+
+            info!("Using {} random seed pairs: {:?}", nbpairs, pair_str(&seed_pairs));
+
+            seed_pairs
+        }
+        // else read pairs from stdin
+        else{
+            info!("Using seed pairs from stdin. --tnbwords option is ignored!"); 
+            utils::read_pairs_from_stdin(env)
+        };
+
+    if std::path::Path::new(outfile).exists() {
+        info!("{} already exists, removing.", outfile);
+        std::fs::remove_file(outfile)
+            .unwrap_or_else(|_| panic!("unable to delete {}", outfile));
+    }
+
+    if !do_mopso {
+        let fitness_fn = pso_train::RelexFitnessFn::new(
+            &seed_pairs,
+            &reference_pairs,
+            &options,
+            env 
+        );
+        info!("starting pso training of relex.");
+        let winner_hyper_params = 
+            pso_train::train_relex(num_particles, iterations, &fitness_fn, outfile);
+        info!("finished pso training of relex.");
+
+        info!("Winner Configuration: {:?}", winner_hyper_params);
+        let final_run_result = relex::do_relex_sync(
+            fitness_fn.dipre_input(), &winner_hyper_params, env);
+
+        info!("Winner Result: {:?}", 
+            pair_str(&pso_train::relex_result_pairs(&final_run_result, env)));
+
+    } else {
+        let fitness_fn = mopso_train::RelexFitnessFn::new(
+            &seed_pairs,
+            &reference_pairs,
+            &options,
+            env 
+        );
+        info!("starting mopso training of relex.");
+        mopso_train::train_relex(num_particles, iterations, &fitness_fn, outfile);
+        info!("finished mopso training of relex.");
+    }
+}
+
+fn run_server(
+    opts: &Options, 
+    matches: &Matches, 
+    program: &str, 
+    env: Env
+    ){
+
+    let port: u16 = match matches.opt_str("d") {
+        None => {
+            print_usage(program, opts);
+            return;
+        }
+        Some(p) => { p.parse().expect("Unable to parse port.") }
+    };
 
-    // service::run_server(env).await;    
+    let mut runtime = tokio::runtime::Runtime::new()
+        .expect("Unable to start tokio runtime.");
 
-    // let set = vec! [
-    //     "London",
-    //     "Berlin",
-    //     "Madrid",
-    //     "Lima"
-    // ];
+    runtime.block_on(service::run_server(env, port));
+}
 
-    // info!("{:?}", set);
-    // let json = CoocInput::new(set);
-    // do_conex(&json, &conex::DEFAULT_CONEX_HYPER_PARAMETER, &env);
+#[derive(Serialize, Deserialize, Default)]
+struct RelexConfig {
+    #[serde(default)]
+    hyper_parameter: relex::RelexHyperParameter,
+    #[serde(default)]
+    seed_pairs: Vec<(String, String)>,
+    #[serde(default)]
+    negative_pairs: Vec<(String, String)>,
+    // several relations extracted together, replaces seed_pairs
+    #[serde(default)]
+    relations: Vec<types::Relation>,
+    // seed tuples of an n-ary relation, replaces seed_pairs
+    #[serde(default)]
+    tuples: Vec<Vec<String>>,
+    // fixed hypernym templates like "X such as Y+", replace seed_pairs
+    #[serde(default)]
+    templates: Vec<String>,
+    // IRI prefixes and relation name of the graph export (see --rg)
+    #[serde(default)]
+    graph: graph::GraphOptions,
+    // options of relation discovery without seed pairs (see --rd)
+    #[serde(default)]
+    discovery: types::DiscoveryOptions,
+    #[serde(default)]
+    options: types::RelexOptions
 }
 
 fn run_relex(
@@ -261,29 +478,191 @@ fn run_relex(
     env: &Env
     ){
 
-    // let config_file = match matches.opt_str("c") {
-    //     None => {
-    //         print_usage(&program, opts);
-    //         return;
-    //     }
-    //     Some(t) => { t }
-    // };
+    let config_file = match matches.opt_str("r") {
+        None => {
+            print_usage(program, opts);
+            return;
+        }
+        Some(t) => { t }
+    };
+
+    let config: RelexConfig = 
+        toml::from_str(&read_to_string(&config_file)
+        .unwrap_or_else(|_| panic!("Unable to open file \"{}\".", &config_file)))
+        .unwrap_or_else(|_| panic!("Unable to read file \"{}\".", &config_file));
+
+    let mut runtime = tokio::runtime::Runtime::new()
+        .expect("Unable to start tokio runtime.");
 
-   
-    // let config: ConexConfig = 
-    //     toml::from_str(&read_to_string(&config_file)
-    //     .unwrap_or_else(|_| panic!("Unable to open file \"{}\".", &config_file)))
-    //     .unwrap_or_else(|_| panic!("Unable to open file \"{}\".", &config_file));
-
-    // conex::do_conex(&types::CoocInput{ set: config.seed_terms }, 
-    //     &config.hyper_parameter, 
-    //     env);
+    // apply a saved pattern set instead of learning patterns
+    if let Some(pattern_file) = matches.opt_str("ra") {
+        let saved = relex::read_patterns(&pattern_file);
+        let result = runtime.block_on(relex::apply_patterns(&saved,
+            &config.options,
+            &config.hyper_parameter,
+            env,
+            types::DefaultLogger::new()));
+
+        print_relex_result(&result, &config.options, &config.graph, env, matches);
+        return;
+    }
+
+    if matches.opt_present("rd") {
+        let clusters = runtime.block_on(relex::discover_relations(&config.discovery,
+            &config.options,
+            &config.hyper_parameter,
+            env,
+            types::DefaultLogger::new()));
+
+        println!("{}", serde_json::to_string_pretty(&clusters)
+            .expect("Could not serialize RelationCluster to JSON String"));
+        return;
+    }
+
+    if !config.templates.is_empty() {
+        if matches.opt_present("rpo") || matches.opt_present("re") {
+            warn!("Template pairs are not written to a pattern file or evaluated.");
+        }
+
+        let result = hearst::do_hearst(&config.templates, &config.options, env);
+
+        println!("{}", serde_json::to_string_pretty(&result)
+            .expect("Could not serialize HearstResult to JSON String"));
+        return;
+    }
+
+    if !config.tuples.is_empty() {
+        if matches.opt_present("rpo") || matches.opt_present("re") {
+            warn!("Tuples are not written to a pattern file or evaluated.");
+        }
+
+        let ti = types::TupleInput {
+            tuples: config.tuples,
+            options: config.options
+        };
+
+        let result = runtime.block_on(nary::do_nary(ti,
+            &config.hyper_parameter,
+            env,
+            types::DefaultLogger::new()));
+
+        println!("{}", serde_json::to_string_pretty(&result)
+            .expect("Could not serialize TupleResult to JSON String"));
+        return;
+    }
+
+    if !config.relations.is_empty() {
+        if matches.opt_present("rpo") {
+            warn!("Patterns of several relations are not written to a pattern file.");
+        }
+        if matches.opt_present("re") {
+            warn!("Several relations are not evaluated against gold pairs.");
+        }
+
+        let ci = types::CoupledDipreInput {
+            relations: config.relations,
+            options: config.options
+        };
+
+        let results = runtime.block_on(relex::do_relex_coupled(ci,
+            &config.hyper_parameter,
+            env,
+            types::DefaultLogger::new()));
+
+        if let Some(format) = matches.opt_str("rg") {
+            print!("{}", graph::to_graph(&results, &config.graph, parse_graph_format(&format)));
+        } else {
+            println!("{}", serde_json::to_string_pretty(&results)
+                .expect("Could not serialize RelationResult to JSON String"));
+        }
+        return;
+    }
+
+    let di = types::DipreInput {
+        pairs: config.seed_pairs,
+        negative_pairs: config.negative_pairs,
+        options: config.options.clone()
+    };
+
+    let result = runtime.block_on(relex::do_relex(di, 
+        &config.hyper_parameter, 
+        env, 
+        types::DefaultLogger::new()));
+
+    if let Some(pattern_file) = matches.opt_str("rpo") {
+        relex::write_patterns(&result.patterns, &pattern_file);
+        info!("{} patterns written to {}.", result.patterns.len(), pattern_file);
+    }
+
+    print_relex_result(&result, &config.options, &config.graph, env, matches);
+}
+
+// sample sentences of explanations (--rx, --cx)
+const EXPLAIN_SENTENCES: usize = 5;
+
+// splits "w1,w2" of --rx
+fn parse_explain_pair(pair: &str) -> (&str, &str) {
+    let mut split = pair.splitn(2, ',');
+    match (split.next(), split.next()) {
+        (Some(w1), Some(w2)) => (w1.trim(), w2.trim()),
+        _ => panic!("Unable to parse pair \"{}\", expected w1,w2.", pair)
+    }
+}
+
+fn parse_graph_format(format: &str) -> graph::GraphFormat {
+    graph::GraphFormat::parse(format)
+        .unwrap_or_else(|| panic!("Unknown graph format \"{}\".", format))
+}
+
+// prints the result, its evaluation against the gold pairs of --re,
+// the explanation of the pair of --rx or the graph of --rg
+fn print_relex_result(result: &types::RelexResult, options: &types::RelexOptions,
+    graph_options: &graph::GraphOptions, env: &Env, matches: &Matches) {
+
+    if let Some(format) = matches.opt_str("rg") {
+        let results = [types::RelationResult {
+            name: graph_options.relation.clone(),
+            result: result.clone()
+        }];
+        print!("{}", graph::to_graph(&results, graph_options, parse_graph_format(&format)));
+    } else if let Some(pair) = matches.opt_str("rx") {
+        let (w1, w2) = parse_explain_pair(&pair);
+        match relex::explain_pair(result, w1, w2, options, env, EXPLAIN_SENTENCES) {
+            Some(explanation) => println!("{}", serde_json::to_string_pretty(&explanation)
+                .expect("Could not serialize PairExplanation to JSON String")),
+            None => warn!("The pair ({}, {}) was not extracted.", w1, w2)
+        }
+    } else if let Some(gold_file) = matches.opt_str("re") {
+        let gold = utils::read_gold_pair_file(&gold_file);
+
+        let ks: Vec<usize> = match matches.opt_str("rk") {
+            Some(ks) => ks.split(',')
+                .map(|k| k.trim().parse()
+                    .unwrap_or_else(|_| panic!("Unable to parse k \"{}\".", k)))
+                .collect(),
+            None => vec!(1, 5, 10, 20, 50)
+        };
+
+        let evaluation = pso_train::evaluate_relex(result, &gold, &ks);
+        info!("precision {:.3}, recall {:.3}, f1 {:.3}.", 
+            evaluation.precision, evaluation.recall, evaluation.f1);
+
+        println!("{}", serde_json::to_string_pretty(&evaluation)
+            .expect("Could not serialize RelexEvaluation to JSON String"));
+    } else {
+        println!("{}", serde_json::to_string_pretty(result)
+            .expect("Could not serialize RelexResult to JSON String"));
+    }
 }
 
 #[derive(Serialize, Deserialize, Default)]
 struct ConexConfig {
     hyper_parameter: conex::ConexHyperParameter,
-    seed_terms: Vec<String>
+    seed_terms: Vec<String>,
+    // restrict results to words mostly tagged with one of these
+    // part-of-speech tags, e.g. ["NOUN", "PROPN"] (needs a conllu import)
+    #[serde(default)]
+    pos_filter: Vec<String>
 }
 
 fn run_conex(
@@ -307,10 +686,28 @@ fn run_conex(
         .unwrap_or_else(|_| panic!("Unable to open file \"{}\".", &config_file)))
         .unwrap_or_else(|_| panic!("Unable to read file \"{}\".", &config_file));
 
-    let result_words = conex::do_conex(&types::CoocInput{ set: config.seed_terms }, 
+    let cooc_input = types::CoocInput{ set: config.seed_terms };
+
+    if let Some(term) = matches.opt_str("cx") {
+        match conex::explain_term(&cooc_input, &term, &config.hyper_parameter, env,
+            EXPLAIN_SENTENCES) {
+            Some(explanation) => println!("{}", serde_json::to_string_pretty(&explanation)
+                .expect("Could not serialize TermExplanation to JSON String")),
+            None => warn!("The term \"{}\" was not extracted.", term)
+        }
+        return;
+    }
+
+    let result_words = conex::do_conex(&cooc_input, 
         &config.hyper_parameter, 
         env);
 
+    let result_words = if config.pos_filter.is_empty() {
+        result_words
+    } else {
+        conex::filter_by_pos(&result_words, &config.pos_filter, env)
+    };
+
     println!("{:?}", result_words.iter()
         .map(|w_nr| env.dict.get_word(w_nr)).collect::<Vec<&str>>());
 }
@@ -328,19 +725,53 @@ fn main() {
     opts.optflag("s", "soundness", "Test soundness of bin-files.");
     opts.optopt("d", "deamon", "Starts REST Server backend.", "PORT");
     opts.optopt("r", "relex", "Starts RELEX with specified input.", "FILE");
+    opts.optopt("", "rpo", "Write the patterns learned by RELEX to FILE.", "FILE");
+    opts.optopt("", "ra", 
+        "Apply the patterns of FILE (see --rpo) instead of learning patterns 
+        from the seed pairs.", "FILE");
+    opts.optopt("", "re", 
+        "Evaluate the RELEX result against the gold pairs of FILE (a tab separated 
+        pair per line, aliases separated by |).", "FILE");
+    opts.optflag("", "rd",
+        "Discover relations without seed pairs, see [discovery] of the relex config.");
+    opts.optopt("", "rk", 
+        "Comma separated k for precision at k. (defaults to 1,5,10,20,50)", "LIST");
+    opts.optopt("", "rx", 
+        "Explain the extraction of PAIR (w1,w2) with its patterns and sentences 
+        instead of printing the result.", "PAIR");
+    opts.optopt("", "rg", 
+        "Print the pairs as a knowledge graph in FORMAT, ntriples, turtle or csv 
+        (see [graph] of the relex config).", "FORMAT");
     opts.optopt("c", "conex", "Starts CONEX with specified input.", "FILE");
+    opts.optopt("", "cx", 
+        "Explain the extraction of TERM with its seed words, first-order 
+        co-occurrences and sentences.", "TERM");
     opts.optopt("t", "train", 
         "Train model parameter with PSO / MOPSO (--tmopso).", "FILE");
     opts.optopt("", "to", "Training outputfile.", "FILE");
     opts.optflag("", "tmopso", "Training with mopso.");
+    opts.optflagopt("", "trelex", 
+        "Train relex instead of conex, the reference file contains a tab separated 
+        pair per line. Relex options are read from FILE.", "FILE");
     opts.optopt("", "tnparticles", "Num particles. (defaults to 100)", "NUM");
     opts.optopt("", "tniter", "Num iterations. (defaults to 100)", "NUM");
-    opts.optopt("", "tnbwords", "Num bootstrap words. (defaults to 5)", "NUM");
-    opts.optopt("x", "import-xml", "Import xml files from directory.", "DIR");
+    opts.optopt("", "tnbwords", "Num bootstrap words / pairs. (defaults to 5)", "NUM");
+    opts.optopt("x", "import-xml", "Import files from directory (see --xf).", "DIR");
+    opts.optopt("", "xf", 
+        "Import format, xml, html, medline (PubMed title and abstract per citation) 
+        or conllu (keeps lemma and pos annotations). (defaults to xml)", "FORMAT");
     opts.optopt("", "xt", "Read specific tag from xml files.", "TAG");
     opts.optopt("", "xl", 
         "Limit the count of documents processed from all xml files.", "LIMIT");
     opts.optopt("", "xp", "Preprocessor function.", "FUNC");
+    opts.optopt("", "xs", 
+        "Word segmenter, unicode or maxmatch for CJK / non-space scripts. 
+        (defaults to unicode)", "SEGMENTER");
+    opts.optopt("", "xlex", "Lexicon file for the maxmatch segmenter.", "FILE");
+    opts.optopt("", "xc", 
+        "Write a checkpoint of the import every NUM documents.", "NUM");
+    opts.optflag("", "xr", 
+        "Resume the import from the checkpoint in the bin-file directory.");
     opts.reqopt("b", "bin-files", 
         "Bin-file directory (if -x is present this directory denotes the
         output directory, otherwise bin-file backup data is read from this directory).", 
@@ -375,8 +806,8 @@ fn main() {
             run_conex(&opts, &matches, &program, &env);
         } else if matches.opt_present("t") {
             run_training(&opts, &matches, &program, &env);
-        } else if matches.opt_present("") {
-            run_server();
+        } else if matches.opt_present("d") {
+            run_server(&opts, &matches, &program, env);
         }
     }
 
@@ -0,0 +1,255 @@
+use super::types::{Env, soundness_test};
+use super::segmentation::Tokenizer;
+use super::checkpoint::{CheckpointConfig, ImportRun};
+use super::xml::{add_document_to_env, file_names_from_directory};
+
+use log::{debug, info, warn};
+use std::io::BufRead;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Citation {
+    pub pmid: String,
+    pub title: String,
+    // (label, text) of every AbstractText, unlabelled
+    // abstracts consist of a single section without label
+    pub abstract_sections: Vec<(Option<String>, String)>
+}
+
+impl Citation {
+    // title and abstract sections, one per line, which separates
+    // them into sentences even if the title lacks a full stop
+    pub fn text(&self) -> String {
+        std::iter::once(self.title.as_str())
+            .chain(self.abstract_sections.iter().map(|(_, t)| t.as_str()))
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<&str>>()
+            .join("\n")
+    }
+}
+
+// reads citations from a PubmedArticleSet or MedlineCitationSet,
+// both contain one MedlineCitation element per citation
+pub struct CitationReader<B: BufRead> {
+    reader: Reader<B>,
+    buf: Vec<u8>,
+    // names of the open elements
+    path: Vec<Vec<u8>>
+}
+
+impl<B: BufRead> CitationReader<B> {
+    pub fn new(reader: Reader<B>) -> CitationReader<B> {
+        CitationReader {
+            reader,
+            buf: Vec::new(),
+            path: Vec::new()
+        }
+    }
+
+    pub fn buffer_position(&self) -> usize {
+        self.reader.buffer_position()
+    }
+}
+
+fn parent_is(path: &[Vec<u8>], name: &[u8]) -> bool {
+    path.len() >= 2 && path[path.len() - 2] == name
+}
+
+impl<B: BufRead> Iterator for CitationReader<B> {
+    type Item = Citation;
+
+    fn next(&mut self) -> Option<Citation> {
+        let mut citation: Option<Citation> = Option::None;
+        // (label, text) of the AbstractText currently read
+        let mut section: Option<(Option<String>, String)> = Option::None;
+        let mut in_title = false;
+        let mut in_pmid = false;
+
+        loop {
+            let event = self.reader.read_event(&mut self.buf);
+            match event {
+                Ok(Event::Start(ref e)) => {
+                    self.path.push(e.name().to_vec());
+
+                    match e.name() {
+                        b"MedlineCitation" => {
+                            citation = Some(Citation::default());
+                        }
+                        b"PMID" if parent_is(&self.path, b"MedlineCitation") => {
+                            in_pmid = true;
+                        }
+                        b"ArticleTitle" if citation.is_some() => {
+                            in_title = true;
+                        }
+                        // AbstractText of OtherAbstract is omitted
+                        b"AbstractText" if citation.is_some()
+                            && parent_is(&self.path, b"Abstract") => {
+                            let reader = &self.reader;
+                            let label = e.attributes()
+                                .filter_map(|a| a.ok())
+                                .find(|a| a.key == b"Label")
+                                .and_then(|a| a.unescape_and_decode_value(reader).ok());
+                            section = Some((label, String::new()));
+                        }
+                        _ => ()
+                    }
+                },
+
+                Ok(Event::End(ref e)) => {
+                    self.path.pop();
+
+                    match e.name() {
+                        b"MedlineCitation" => {
+                            self.buf.clear();
+                            return citation;
+                        }
+                        b"PMID" => { in_pmid = false; }
+                        b"ArticleTitle" => { in_title = false; }
+                        b"AbstractText" => {
+                            if let (Some(c), Some((label, text))) =
+                                (citation.as_mut(), section.take()) {
+                                c.abstract_sections.push((label, text.trim().to_owned()));
+                            }
+                        }
+                        _ => ()
+                    }
+                },
+
+                Ok(Event::Text(ref e)) => {
+                    if let Some(c) = citation.as_mut() {
+                        if in_pmid || in_title || section.is_some() {
+                            let s = e.unescape_and_decode(&self.reader)
+                                .expect("Error while reading text from xml.");
+
+                            if in_pmid {
+                                c.pmid.push_str(s.trim());
+                            } else if in_title {
+                                c.title.push_str(&s);
+                            } else if let Some((_, text)) = section.as_mut() {
+                                text.push_str(&s);
+                            }
+                        }
+                    }
+                },
+
+                Err(e) => panic!(
+                    "Error at position {}: {:?}", self.reader.buffer_position(), e),
+                Ok(Event::Eof) => {
+                    self.buf.clear();
+                    return Option::None;
+                }
+                _ => (),
+            }
+            self.buf.clear();
+        }
+    }
+}
+
+pub fn read_medline_and_persist_env(
+    input_dir: &str,
+    output_dir: &str,
+    limit: Option<usize>,
+    tokenizer: &Tokenizer,
+    checkpointing: &CheckpointConfig) {
+
+    info!("starting read_medline_and_persist_env.");
+    info!("reading files from directory {}.", input_dir);
+
+    let files: Vec<String> = file_names_from_directory(input_dir)
+        .expect("Unable to read file names from directory {}!")
+        .into_iter()
+        .filter(|f| {
+            if f.ends_with(".gz") {
+                warn!("skipping {}, compressed files need to be unpacked first.", f);
+            }
+            f.ends_with(".xml")
+        })
+        .collect();
+
+    let (env, run) = read_medlines_to_env(&files, limit, tokenizer,
+        checkpointing, output_dir);
+
+    info!("done reading files from directory.");
+
+    info!("{} citations with {} sentences loaded, with {} distinct words.",
+        env.documents.documents.len(),
+        env.sentences.sentences.len(),
+        env.dict.dict_vec.len());
+
+    info!("Starting soundness test.");
+    soundness_test(&env);
+    info!("Done soundness test.");
+
+    info!("starting writing binary files.");
+    env.serialize(output_dir.to_owned());
+    info!("done writing binary files.");
+
+    run.finish(0);
+
+    info!("done read_medline_and_persist_env.");
+}
+
+pub fn read_medlines_to_env<'a>(
+    files: &[String],
+    limit: Option<usize>,
+    tokenizer: &Tokenizer,
+    checkpointing: &'a CheckpointConfig,
+    output_dir: &'a str) -> (Env, ImportRun<'a>) {
+
+    let (mut env, mut run) = ImportRun::start(files, checkpointing, output_dir);
+
+    for file_name in files {
+        if run.limit_reached(limit) { break; }
+
+        if ! run.start_file(file_name) { continue; }
+
+        if process_medline_file(file_name, &mut env, limit, tokenizer, &mut run) {
+            run.finish_file();
+        }
+    }
+
+    (env, run)
+}
+
+// returns true if the file was read to the end
+fn process_medline_file(
+    file_name: &str,
+    env: &mut Env,
+    limit: Option<usize>,
+    tokenizer: &Tokenizer,
+    run: &mut ImportRun) -> bool {
+
+    let reader = Reader::from_file(file_name)
+        .expect("Could not read from input file.");
+
+    let mut citations = CitationReader::new(reader);
+
+    // citations processed by a former run are skipped
+    let skip = run.checkpoint.docs_to_skip(file_name);
+
+    info!("Start reading file {}", file_name);
+
+    let mut file_count = 0usize;
+    while let Some(citation) = citations.next() {
+        file_count += 1;
+        if file_count <= skip { continue; }
+
+        if run.limit_reached(limit) {
+            info!("document limit reached.");
+            return false;
+        }
+
+        if citation.abstract_sections.is_empty() {
+            debug!("citation {} has no abstract.", citation.pmid);
+        }
+
+        add_document_to_env(&citation.pmid, &citation.title,
+            &citation.text(), env, tokenizer);
+
+        run.document_done(env, citations.buffer_position() as u64);
+    }
+
+    info!("done reading file.");
+    true
+}
@@ -0,0 +1,75 @@
+use super::*;
+use medline::{Citation, CitationReader};
+use quick_xml::Reader;
+
+#[test]
+fn test_pubmed_article_set(){
+    let xml = r#"<?xml version="1.0" ?>
+<PubmedArticleSet>
+<PubmedArticle>
+  <MedlineCitation Status="MEDLINE" Owner="NLM">
+    <PMID Version="1">10021</PMID>
+    <Article PubModel="Print">
+      <ArticleTitle>BRCA1 and <i>TP53</i> in breast cancer.</ArticleTitle>
+      <Abstract>
+        <AbstractText Label="BACKGROUND" NlmCategory="BACKGROUND">BRCA1 is a tumor suppressor.</AbstractText>
+        <AbstractText Label="RESULTS" NlmCategory="RESULTS">TP53 &amp; BRCA1 interact.</AbstractText>
+      </Abstract>
+    </Article>
+    <OtherAbstract Type="Publisher"><AbstractText>Ignored.</AbstractText></OtherAbstract>
+    <CommentsCorrectionsList>
+      <CommentsCorrections RefType="Cites"><PMID Version="1">999</PMID></CommentsCorrections>
+    </CommentsCorrectionsList>
+  </MedlineCitation>
+  <PubmedData><ArticleIdList><ArticleId IdType="pubmed">10021</ArticleId></ArticleIdList></PubmedData>
+</PubmedArticle>
+<PubmedArticle>
+  <MedlineCitation>
+    <PMID Version="1">10022</PMID>
+    <Article><ArticleTitle>No abstract here</ArticleTitle></Article>
+  </MedlineCitation>
+</PubmedArticle>
+</PubmedArticleSet>"#;
+
+    let citations: Vec<Citation> =
+        CitationReader::new(Reader::from_str(xml)).collect();
+
+    assert_eq!(citations, vec![
+        Citation {
+            pmid: "10021".to_owned(),
+            title: "BRCA1 and TP53 in breast cancer.".to_owned(),
+            abstract_sections: vec![
+                (Some("BACKGROUND".to_owned()), "BRCA1 is a tumor suppressor.".to_owned()),
+                (Some("RESULTS".to_owned()), "TP53 & BRCA1 interact.".to_owned())
+            ]
+        },
+        Citation {
+            pmid: "10022".to_owned(),
+            title: "No abstract here".to_owned(),
+            abstract_sections: vec![]
+        }
+    ]);
+
+    assert_eq!(citations[0].text(),
+        "BRCA1 and TP53 in breast cancer.\nBRCA1 is a tumor suppressor.\nTP53 & BRCA1 interact.");
+}
+
+#[test]
+fn test_medline_citation_set(){
+    let xml = r#"<MedlineCitationSet>
+<MedlineCitation Owner="NLM">
+<PMID>42</PMID>
+<Article><ArticleTitle>Title.</ArticleTitle>
+<Abstract><AbstractText>Plain abstract.</AbstractText></Abstract></Article>
+</MedlineCitation>
+<DeleteCitation><PMID>43</PMID></DeleteCitation>
+</MedlineCitationSet>"#;
+
+    let citations: Vec<Citation> =
+        CitationReader::new(Reader::from_str(xml)).collect();
+
+    assert_eq!(citations.len(), 1);
+    assert_eq!(citations[0].pmid, "42");
+    assert_eq!(citations[0].abstract_sections,
+        vec![(None, "Plain abstract.".to_owned())]);
+}
@@ -126,3 +126,89 @@ pub fn train<'a>(
             append_swarm_to_file(_i, swarm, out_file);
         });
 }
+
+pub struct RelexFitnessFn<'a> {
+    fitness: pso_train::RelexFitnessFn<'a>
+}
+
+impl RelexFitnessFn<'_> {
+    pub fn new<'a>(
+        seed_pairs: &'a [types::ArgumentPair], 
+        reference_pairs: &'a [types::ArgumentPair],
+        options: &'a types::RelexOptions,
+        env: &'a Env) -> RelexFitnessFn<'a> {
+
+        RelexFitnessFn {
+            fitness: pso_train::RelexFitnessFn::new(
+                seed_pairs, reference_pairs, options, env)
+        }
+    }
+
+    pub fn fitness(&self, swarm: &mut mopso::Swarm){
+        let o = &swarm.o;
+        swarm.particles.axis_iter_mut(Axis(0))
+            .into_par_iter()
+            .for_each(|mut p| {
+                let pos = p.slice(s![o.p.0..o.p.1]); 
+                let hyper_params = 
+                    relex::RelexHyperParameter::from_vector(pos.to_vec(), 0f64);
+                let (precision, recall) = self.fitness.evaluate(&hyper_params);
+
+                p[o.f.0] = precision;
+                p[o.f.0+1] = recall;
+
+            });
+    }
+}
+
+pub fn train_relex<'a>(
+    num_particles: usize,
+    iterations: usize,
+    fitness: &'a RelexFitnessFn,
+    out_file: &'a str){
+
+    let position_bounds = array![
+        [ -100f64, 100f64 ], // pattern_wpair_boost
+        [ -100f64, 100f64 ], // pattern_pattern_boost
+        [ -100f64, 100f64 ], // pattern_short_sized_boost
+        [ -100f64, 100f64 ], // pattern_medium_sized_boost
+        [ -100f64, 100f64 ], // pattern_long_sized_boost
+        [ -1000f64, 1000f64 ], // wpair_word_global_frequency_boost_per_sentence
+        [ -100f64, 100f64 ], // wpair_pattern_boost
+        ];
+  
+    let mut swarm = mopso::Swarm::new(
+       num_particles, 
+       7,
+       position_bounds,
+       2,
+       array![
+       [0.0, 1.0],
+       [0.0, 1.0],
+       ],
+       array![true, true],
+       0,
+       &|swarm: &mut mopso::Swarm| {
+           fitness.fitness(swarm);
+       },
+       &|_i: usize, swarm: &mut mopso::Swarm| {
+           info!("{} of {}", _i, iterations);
+           append_swarm_to_file(_i, swarm, out_file);
+       }
+    );
+
+    swarm.fly(
+        iterations,
+        &pso::HyperParams {
+            learning_cognitive: 0.2,
+            learning_social: 0.2,
+            inertia: 0.02
+        },
+        &|swarm: &mut mopso::Swarm| {
+            fitness.fitness(swarm);
+        },
+        &|_i: usize, swarm: &mut mopso::Swarm| {
+            info!("{} of {}", _i, iterations);
+            append_swarm_to_file(_i, swarm, out_file);
+        });
+}
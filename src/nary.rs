@@ -0,0 +1,373 @@
+use super::types::{AsyncLogger, Env, WordNr, SentenceId, RelexArguments, RelexOptions,
+TupleInput, TupleResult, ScoredTuple, ScoredTuplePattern, is_concrete_word};
+use super::relex::{RelexHyperParameter, ArgumentSpans, match_infix, pattern_size_boost,
+argument_frequency_boost, find_sentences};
+
+use log::warn;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+// n-ary relations: the arguments of a tuple are separated by infix
+// segments, patterns and tuples are scored like the patterns and
+// wpairs of relex (with the same hyper parameters)
+
+// the arguments of one or more words
+type Tuple = Vec<Vec<WordNr>>;
+
+// patterns are identified by their segments and argument order
+type TuplePatternKey = (Vec<Vec<WordNr>>, Vec<usize>);
+
+#[derive(Debug, Clone)]
+struct TuplePattern {
+    segments: Vec<Vec<WordNr>>,
+    // order[i] is the argument in front of segment i
+    order: Vec<usize>,
+    fitness: f64
+}
+
+impl TuplePattern {
+    fn key(&self) -> TuplePatternKey {
+        (self.segments.clone(), self.order.clone())
+    }
+}
+
+// the pattern of the arguments placed at the given ranges (sorted by
+// position), None if arguments overlap or are too far apart
+fn tuple_pattern(placed: &[(usize, (usize, usize))], sent: &[WordNr], s_id: &SentenceId,
+    spans: &ArgumentSpans, options: &RelexOptions, env: &Env) -> Option<TuplePattern> {
+
+    let mut segments = Vec::new();
+
+    for pair in placed.windows(2) {
+        let (_, (_, mut a)) = pair[0];
+        let (_, (mut b, _)) = pair[1];
+
+        // adjacent or overlapping arguments
+        if b <= a {
+            return None;
+        }
+
+        // as in relex skipped words next to the arguments
+        // are not part of the segments
+        if spans.arguments != RelexArguments::Token {
+            while b - a > 1 && spans.is_skipped(sent, s_id, a, env) {
+                a += 1;
+            }
+            while b - a > 1 && spans.is_skipped(sent, s_id, b - 1, env) {
+                b -= 1;
+            }
+        }
+
+        if options.max_pair_distance > 0 && b - a > options.max_pair_distance {
+            return None;
+        }
+
+        segments.push(sent[a..b].to_vec());
+    }
+
+    Some(TuplePattern {
+        segments,
+        order: placed.iter().map(|(argument, _)| *argument).collect(),
+        fitness: 0.0
+    })
+}
+
+// one pattern for every combination of the argument positions in the sentence
+fn extract_tuple_patterns(tuple: &Tuple, s_id: &SentenceId, spans: &ArgumentSpans,
+    options: &RelexOptions, env: &Env) -> Vec<TuplePattern> {
+
+    let sent = env.get_sentence(s_id);
+
+    let ranges: Vec<Vec<(usize, usize)>> = tuple.iter()
+        .map(|argument| (0..sent.len())
+            .filter(|i| sent[*i..].starts_with(argument))
+            .map(|i| (i, i + argument.len()))
+            .collect())
+        .collect();
+
+    let mut patterns = Vec::new();
+
+    if ranges.iter().any(|r| r.is_empty()) {
+        return patterns;
+    }
+
+    let mut combination = vec![0; tuple.len()];
+    loop {
+        let mut placed: Vec<(usize, (usize, usize))> = combination.iter().enumerate()
+            .map(|(argument, c)| (argument, ranges[argument][*c]))
+            .collect();
+        placed.sort_unstable_by_key(|(_, range)| *range);
+
+        if let Some(pattern) = tuple_pattern(&placed, sent, s_id, spans, options, env) {
+            patterns.push(pattern);
+        }
+
+        // next combination, counting up like an odometer
+        let mut i = 0;
+        loop {
+            if i == combination.len() {
+                return patterns;
+            }
+            combination[i] += 1;
+            if combination[i] < ranges[i].len() {
+                break;
+            }
+            combination[i] = 0;
+            i += 1;
+        }
+    }
+}
+
+// learns tuple patterns from the sentences containing the tuples,
+// returns the patterns surviving the threshold sorted by fitness
+fn learn_tuple_patterns(tuples: &[Tuple], spans: &ArgumentSpans, options: &RelexOptions,
+    hyper_params: &RelexHyperParameter, env: &Env) -> Vec<TuplePattern> {
+
+    let mut pattern_cache: HashMap<TuplePatternKey, TuplePattern> = HashMap::new();
+
+    for tuple in tuples {
+        let mut already_tuple_boosted: HashSet<TuplePatternKey> = HashSet::new();
+
+        for s_id in find_sentences(tuple.iter().flatten(), env) {
+            for pattern in extract_tuple_patterns(tuple, &s_id, spans, options, env) {
+                let key = pattern.key();
+
+                let p = pattern_cache.entry(key.clone())
+                    .or_insert_with(|| {
+                        let len = pattern.segments.iter().map(|s| s.len()).sum();
+                        TuplePattern {
+                            fitness: pattern_size_boost(len, hyper_params),
+                            ..pattern
+                        }
+                    });
+
+                // boost once per tuple and for every occurrence, see relex
+                if already_tuple_boosted.insert(key) {
+                    p.fitness += hyper_params.pattern_wpair_boost;
+                }
+                p.fitness += hyper_params.pattern_pattern_boost;
+            }
+        }
+    }
+
+    let mut patterns: Vec<TuplePattern> = pattern_cache.into_values()
+        .filter(|pattern| pattern.fitness >= hyper_params.pattern_survivor_threshold)
+        .collect();
+    patterns.sort_unstable_by(
+        |a, b| b.fitness.partial_cmp(&a.fitness)
+            .unwrap_or(std::cmp::Ordering::Equal));
+
+    patterns
+}
+
+// matches the segments from position pos on, returns the
+// ranges of the arguments following the segments
+fn match_segments(segments: &[Vec<WordNr>], sent: &[WordNr], pos: usize,
+    s_id: &SentenceId, spans: &ArgumentSpans, env: &Env) -> Option<Vec<(usize, usize)>> {
+
+    let (segment, rest) = segments.split_first()?;
+    let end = match_infix(segment, sent, pos, s_id, env)?;
+
+    let argument = if spans.arguments == RelexArguments::Token {
+        Some((end, end + 1)).filter(|(_, e)| *e <= sent.len())
+    } else {
+        spans.argument(sent, s_id, end, false, env)
+    }?;
+
+    if rest.is_empty() {
+        return Some(vec!(argument));
+    }
+
+    let mut next = argument.1;
+    if spans.arguments != RelexArguments::Token {
+        while next < sent.len() && spans.is_skipped(sent, s_id, next, env) {
+            next += 1;
+        }
+    }
+
+    let mut arguments = match_segments(rest, sent, next, s_id, spans, env)?;
+    arguments.insert(0, argument);
+    Some(arguments)
+}
+
+// every match of the segments yields a tuple
+fn find_tuples(pattern: &TuplePattern, spans: &ArgumentSpans, env: &Env) -> Vec<Tuple> {
+
+    let concrete = pattern.segments.iter()
+        .flatten()
+        .filter(|w| is_concrete_word(**w));
+
+    let mut tuples = Vec::new();
+
+    for s_id in find_sentences(concrete, env) {
+        let sent = env.get_sentence(&s_id);
+
+        for start in 1..sent.len() {
+            let first = if spans.arguments == RelexArguments::Token {
+                Some((start - 1, start))
+            } else {
+                spans.argument(sent, &s_id, start - 1, true, env)
+            };
+
+            let rest = match_segments(&pattern.segments, sent, start, &s_id, spans, env);
+
+            if let (Some(first), Some(rest)) = (first, rest) {
+                let mut tuple: Tuple = vec!(Vec::new(); pattern.order.len());
+                for (argument, (a, b)) in pattern.order.iter()
+                    .zip(std::iter::once(first).chain(rest)) {
+                    tuple[*argument] = sent[a..b].to_vec();
+                }
+                tuples.push(tuple);
+            }
+        }
+    }
+
+    tuples
+}
+
+// scores the tuples found by the patterns like the wpairs of relex,
+// returns the tuples surviving the threshold sorted by fitness, each
+// with the indices of the patterns which found it
+fn score_tuples(pattern_on_tuples: Vec<Vec<Tuple>>, hyper_params: &RelexHyperParameter,
+    env: &Env) -> Vec<(Tuple, f64, Vec<usize>)> {
+
+    let word_frequency_boost =
+        hyper_params.wpair_word_global_frequency_boost_per_sentence
+        / env.sentences.sentences.len() as f64;
+
+    let mut tuple_cache: HashMap<Tuple, (f64, Vec<usize>)> = HashMap::new();
+
+    for (pattern_idx, tuples) in pattern_on_tuples.into_iter().enumerate() {
+        for tuple in tuples {
+            let (fitness, patterns) = tuple_cache.entry(tuple)
+                .or_insert_with_key(|tuple| {
+                    let frequency_boost = tuple.iter()
+                        .map(|argument| argument_frequency_boost(argument,
+                            word_frequency_boost, env))
+                        .sum();
+                    (frequency_boost, Vec::new())
+                });
+
+            if !patterns.contains(&pattern_idx) {
+                *fitness += hyper_params.wpair_pattern_boost;
+                patterns.push(pattern_idx);
+            }
+        }
+    }
+
+    let mut tuples: Vec<(Tuple, f64, Vec<usize>)> = tuple_cache.into_iter()
+        .filter(|(_, (fitness, _))| *fitness >= hyper_params.wpair_survivor_threshold)
+        .map(|(tuple, (fitness, patterns))| (tuple, fitness, patterns))
+        .collect();
+    tuples.sort_unstable_by(
+        |(_, a, _), (_, b, _)| b.partial_cmp(a)
+            .unwrap_or(std::cmp::Ordering::Equal));
+
+    tuples
+}
+
+// bootstrapping of tuples like do_relex: every round learns patterns
+// from the seed tuples, finds new tuples and promotes the best ones.
+// Uses the argument, distance and bootstrapping options of RelexOptions.
+pub async fn do_nary<F: AsyncLogger>(
+    ti: TupleInput, hyper_params: &RelexHyperParameter, env: &Env, mut log: F)
+    -> TupleResult {
+
+    // the arity of the first seed tuple, tuples of another arity and
+    // tuples with unknown arguments are removed
+    let arity = ti.tuples.first().map(|t| t.len()).unwrap_or(0);
+    if arity < 2 {
+        warn!("Seed tuples need an arity of at least 2.");
+        return TupleResult::default();
+    }
+
+    let mut seeds: Vec<Tuple> = ti.tuples.iter()
+        .filter(|tuple| {
+            if tuple.len() != arity {
+                warn!("Tuple {:?} has not arity {} - removing it from seed tuples.",
+                    tuple, arity);
+            }
+            tuple.len() == arity
+        })
+        .filter_map(|tuple| tuple.iter()
+            .map(|argument| {
+                let argument_nrs = env.get_argument_nrs(argument);
+                if argument_nrs.is_none() {
+                    warn!("Argument \"{}\" not found in dictionary \
+                        - removing tuple {:?} from seed tuples.", argument, tuple);
+                }
+                argument_nrs
+            })
+            .collect::<Option<Tuple>>())
+        .collect();
+    let mut known: HashSet<Tuple> = seeds.iter().cloned().collect();
+
+    let options = &ti.options;
+    let spans = ArgumentSpans::new(options, env);
+    let iterations = options.iterations.max(1);
+
+    let mut patterns = Vec::new();
+    let mut tuples = Vec::new();
+
+    for round in 1..=iterations {
+        log.log(format!("starting round {} of {} with {} seed tuples of arity {}.",
+            round, iterations, seeds.len(), arity)).await;
+
+        patterns = learn_tuple_patterns(&seeds, &spans, options, hyper_params, env);
+        log.log(format!("{} tuple patterns left after applying threshold fitness of {}.",
+            patterns.len(), hyper_params.pattern_survivor_threshold)).await;
+
+        let pattern_on_tuples = patterns.iter()
+            .map(|pattern| find_tuples(pattern, &spans, env))
+            .collect();
+
+        tuples = score_tuples(pattern_on_tuples, hyper_params, env);
+        log.log(format!("{} tuples left after applying threshold fitness of {}.",
+            tuples.len(), hyper_params.wpair_survivor_threshold)).await;
+
+        if round == iterations {
+            break;
+        }
+
+        let promoted: Vec<Tuple> = tuples.iter()
+            .filter(|(tuple, _, _)| !known.contains(tuple))
+            .take(if options.max_new_pairs_per_round == 0 { usize::MAX }
+                else { options.max_new_pairs_per_round })
+            .map(|(tuple, _, _)| tuple.clone())
+            .collect();
+
+        log.log(format!("round {}: promoting {} new seed tuples.",
+            round, promoted.len())).await;
+
+        if promoted.is_empty() {
+            log.log("no new seed tuples, stopping.".to_owned()).await;
+            break;
+        }
+
+        known.extend(promoted.iter().cloned());
+        seeds.extend(promoted);
+    }
+
+    TupleResult {
+        patterns: patterns.iter()
+            .map(|pattern| ScoredTuplePattern {
+                segments: pattern.segments.iter()
+                    .map(|segment| segment.iter()
+                        .map(|w| env.get_infix_word(w))
+                        .collect())
+                    .collect(),
+                order: pattern.order.clone(),
+                fitness: pattern.fitness
+            })
+            .collect(),
+        tuples: tuples.iter()
+            .map(|(tuple, fitness, pattern_idxs)| ScoredTuple {
+                arguments: tuple.iter()
+                    .map(|argument| env.get_argument(argument))
+                    .collect(),
+                fitness: *fitness,
+                patterns: pattern_idxs.clone()
+            })
+            .collect()
+    }
+}
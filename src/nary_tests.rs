@@ -0,0 +1,76 @@
+use super::*;
+
+#[test]
+fn test_nary_tuples() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Einstein won the Nobel Prize in 1921. \
+        Curie won the Nobel Prize in 1911. \
+        Bohr won the Nobel Prize in 1922. \
+        Einstein received the Nobel Prize in 1921. \
+        Curie received the Nobel Prize in 1911. \
+        Bohr received the Nobel Prize in 1922. \
+        Fleming won the Lasker Award in 1946.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let hp = relex::RelexHyperParameter::default();
+
+    let ti = types::TupleInput {
+        tuples: vec!(
+            vec!("Einstein".to_owned(), "Nobel Prize".to_owned(), "1921".to_owned()),
+            vec!("Curie".to_owned(), "Nobel Prize".to_owned(), "1911".to_owned())),
+        options: types::RelexOptions {
+            arguments: types::RelexArguments::Capitalised,
+            ..Default::default()
+        }
+    };
+
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(nary::do_nary(ti, &hp, &env, types::DefaultLogger::new()));
+
+    let mut segments: Vec<Vec<Vec<String>>> = result.patterns.iter()
+        .map(|p| p.segments.clone())
+        .collect();
+    segments.sort();
+    assert_eq!(segments, vec!(vec!(vec!("received"), vec!("in")), vec!(vec!("won"), vec!("in"))));
+    assert_eq!(result.patterns[0].order, vec!(0, 1, 2));
+
+    let tuples: Vec<(Vec<&str>, f64)> = result.tuples.iter()
+        .map(|t| (t.arguments.iter().map(|a| a.as_str()).collect(), t.fitness))
+        .collect();
+    assert_eq!(tuples.len(), 3);
+    // found by both patterns, the frequency malus of rare words is 0
+    assert!(tuples.contains(&(vec!("Bohr", "Nobel Prize", "1922"), 20.0)));
+    // found by a single pattern only
+    assert!(!tuples.iter().any(|(t, _)| t[0] == "Fleming"));
+}
+
+#[test]
+fn test_nary_invalid_seeds() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Einstein won in 1921. \
+        Curie won in 1911.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let run = |tuples: Vec<Vec<&str>>| {
+        let ti = types::TupleInput {
+            tuples: tuples.iter()
+                .map(|t| t.iter().map(|a| a.to_string()).collect())
+                .collect(),
+            options: types::RelexOptions::default()
+        };
+        tokio::runtime::Runtime::new().unwrap()
+            .block_on(nary::do_nary(ti, &relex::RelexHyperParameter::default(), &env,
+                types::DefaultLogger::new()))
+    };
+
+    // too small arity
+    assert_eq!(run(vec!(vec!("Einstein"))), types::TupleResult::default());
+
+    // tuples of another arity and with unknown arguments are skipped,
+    // the remaining seeds are used
+    let result = run(vec!(
+        vec!("Einstein", "1921"),
+        vec!("Curie", "1911"),
+        vec!("Curie"),
+        vec!("Bohr", "1922")));
+    assert_eq!(result.patterns.len(), 1);
+    assert_eq!(result.patterns[0].segments, vec!(vec!("won", "in")));
+}
@@ -24,12 +24,12 @@ fn append_swarm_to_file(s: &pso::Swarm, f: &str){
     writeln!(file, "\n").unwrap();
 }
 
-pub fn calc_precision_recall(
-    retrival_erg: &[WordNr],
-    reference: &[WordNr]) -> (f64, f64) {
+pub fn calc_precision_recall<T: Eq + std::hash::Hash>(
+    retrival_erg: &[T],
+    reference: &[T]) -> (f64, f64) {
 
-    let retrival_erg: HashSet<&WordNr> = retrival_erg.iter().collect();
-    let reference: HashSet<&WordNr> = reference.iter().collect();
+    let retrival_erg: HashSet<&T> = retrival_erg.iter().collect();
+    let reference: HashSet<&T> = reference.iter().collect();
 
     let true_positives: HashSet<_> = retrival_erg.intersection(&reference).collect();
 
@@ -141,3 +141,204 @@ pub fn train<'a>(
     let leader_pos = swarm.particles.slice(s![swarm.leader, swarm.o.p.0..swarm.o.p.1]);
     conex::ConexHyperParameter::from_vector(leader_pos.to_vec(), 0f64)
 }
+
+// pairs of the relex result as word numbers, arguments of several
+// words included
+pub fn relex_result_pairs(result: &types::RelexResult, env: &Env) -> Vec<types::ArgumentPair> {
+    result.pairs.iter()
+        .filter_map(|p| Some((env.get_argument_nrs(&p.w1)?, env.get_argument_nrs(&p.w2)?)))
+        .collect()
+}
+
+pub fn f1(precision: f64, recall: f64) -> f64 {
+    // f1 of zero for no true positives
+    if precision + recall > 0.0 {
+        2.0 * ((precision * recall) / (precision + recall))
+    } else {
+        0.0
+    }
+}
+
+// precision / recall of the result pairs against gold pairs with aliases,
+// precision at every k of ks and the precision of every pattern
+pub fn evaluate_relex(result: &types::RelexResult, gold: &[types::GoldPair], ks: &[usize])
+    -> types::RelexEvaluation {
+
+    let mut gold_on_alias: HashMap<(&str, &str), usize> = HashMap::new();
+    for (i, g) in gold.iter().enumerate() {
+        for w1 in &g.w1 {
+            for w2 in &g.w2 {
+                gold_on_alias.entry((w1.as_str(), w2.as_str())).or_insert(i);
+                // pairs of a symmetric relation match in either order
+                if result.symmetric {
+                    gold_on_alias.entry((w2.as_str(), w1.as_str())).or_insert(i);
+                }
+            }
+        }
+    }
+
+    // pairs matching an alias of a gold pair are identified by its index
+    let found: Vec<Option<usize>> = result.pairs.iter()
+        .map(|p| gold_on_alias.get(&(p.w1.as_str(), p.w2.as_str())).copied())
+        .collect();
+
+    // precision counts every correct pair of the ranked list, like the
+    // precision at k, so precision is the precision at k of all pairs.
+    // Recall counts several aliases of one gold pair once.
+    let correct = |pairs: &[Option<usize>]| pairs.iter().filter(|f| f.is_some()).count();
+    let precision = if found.is_empty() {
+        0.0
+    } else {
+        correct(&found) as f64 / found.len() as f64
+    };
+    let found_gold: HashSet<usize> = found.iter().flatten().copied().collect();
+    let recall = if gold.is_empty() {
+        0.0
+    } else {
+        found_gold.len() as f64 / gold.len() as f64
+    };
+
+    let precision_at_k = ks.iter()
+        .filter(|k| **k > 0)
+        .map(|k| (*k, correct(&found[..found.len().min(*k)]) as f64 / *k as f64))
+        .collect();
+
+    let patterns = result.patterns.iter().enumerate()
+        .map(|(i, pattern)| {
+            let (pairs, correct) = result.pairs.iter().zip(&found)
+                .filter(|(p, _)| p.patterns.contains(&i))
+                .fold((0, 0), |(pairs, correct), (_, f)|
+                    (pairs + 1, correct + f.is_some() as usize));
+
+            types::PatternEvaluation {
+                infix: pattern.infix.clone(),
+                fitness: pattern.fitness,
+                pairs,
+                correct,
+                precision: if pairs == 0 { 0.0 } else { correct as f64 / pairs as f64 }
+            }
+        }).collect();
+
+    types::RelexEvaluation {
+        precision,
+        recall,
+        f1: f1(precision, recall),
+        precision_at_k,
+        patterns
+    }
+}
+
+pub struct RelexFitnessFn<'a> {
+    env: &'a Env, 
+    seed_pairs: &'a [types::ArgumentPair], 
+    // in the order of the result pairs, see relex::canonical_pair
+    reference_pairs: Vec<types::ArgumentPair>,
+    options: &'a types::RelexOptions
+}
+
+impl RelexFitnessFn<'_> {
+    pub fn new<'a>(
+        seed_pairs: &'a [types::ArgumentPair], 
+        reference_pairs: &'a [types::ArgumentPair],
+        options: &'a types::RelexOptions,
+        env: &'a Env) -> RelexFitnessFn<'a> {
+
+        let reference_pairs = reference_pairs.iter()
+            .map(|(w1, w2)| relex::canonical_pair(w1.clone(), w2.clone(), options.symmetric))
+            .collect();
+
+        RelexFitnessFn {
+            env,
+            seed_pairs,
+            reference_pairs,
+            options
+        }
+    }
+
+    pub fn dipre_input(&self) -> types::DipreInput {
+        types::DipreInput {
+            pairs: self.seed_pairs.iter()
+                .map(|(w1, w2)| (self.env.get_argument(w1), self.env.get_argument(w2)))
+                .collect(),
+            negative_pairs: Vec::new(),
+            options: self.options.clone()
+        }
+    }
+
+    // precision and recall of the extracted pairs
+    pub fn evaluate(&self, hyper_params: &relex::RelexHyperParameter) -> (f64, f64) {
+        let relex_res = relex::do_relex_sync(self.dipre_input(), hyper_params, self.env);
+        calc_precision_recall(&relex_result_pairs(&relex_res, self.env),
+            &self.reference_pairs)
+    }
+
+    pub fn fitness(&self, swarm: &mut pso::Swarm){
+        let o = &swarm.o;
+        swarm.particles.axis_iter_mut(Axis(0))
+            .into_par_iter()
+            .for_each(|mut p| {
+                let pos = p.slice(s![o.p.0..o.p.1]); 
+                let hyper_params = 
+                    relex::RelexHyperParameter::from_vector(pos.to_vec(), 0f64);
+                let (precision, recall) = self.evaluate(&hyper_params);
+
+                // write fitness fn
+                p[o.f] = f1(precision, recall);
+
+                // write precision and recall
+                p[o.l.0] = precision;
+                p[o.l.0+1] = recall;
+
+            });
+    }
+}
+
+pub fn train_relex<'a>(
+    num_particles: usize, 
+    iterations: usize, 
+    fitness: &'a RelexFitnessFn,
+    out_file: &'a str) -> relex::RelexHyperParameter {
+
+    let position_bounds = array![
+        [ -100f64, 100f64 ], // pattern_wpair_boost
+        [ -100f64, 100f64 ], // pattern_pattern_boost
+        [ -100f64, 100f64 ], // pattern_short_sized_boost
+        [ -100f64, 100f64 ], // pattern_medium_sized_boost
+        [ -100f64, 100f64 ], // pattern_long_sized_boost
+        [ -1000f64, 1000f64 ], // wpair_word_global_frequency_boost_per_sentence
+        [ -100f64, 100f64 ], // wpair_pattern_boost
+        ];
+  
+    let mut swarm = pso::Swarm::new(
+       num_particles, 
+       7,
+       position_bounds,
+       (-1.0, 2.0),
+       2,
+       &|swarm: &mut pso::Swarm| {
+           fitness.fitness(swarm);
+       },
+       &|_i: usize, swarm: &mut pso::Swarm| {
+           info!("{} of {}", _i, iterations);
+           append_swarm_to_file(swarm, out_file);
+       }
+    );
+
+    swarm.fly(
+        iterations,
+        &pso::HyperParams {
+            learning_cognitive: 0.2,
+            learning_social: 0.2,
+            inertia: 0.02
+        },
+        &|swarm: &mut pso::Swarm| {
+            fitness.fitness(swarm);
+        },
+        &|_i: usize, swarm: &mut pso::Swarm| {
+            info!("{} of {}", _i, iterations);
+            append_swarm_to_file(swarm, out_file);
+        });
+
+    let leader_pos = swarm.particles.slice(s![swarm.leader, swarm.o.p.0..swarm.o.p.1]);
+    relex::RelexHyperParameter::from_vector(leader_pos.to_vec(), 0f64)
+}
@@ -12,6 +12,101 @@ fn test_precision_recall() {
     assert_approx_eq!(4.0 / 10.0, recall);
 }
 
+#[test]
+fn test_precision_recall_pairs() {
+    let reference = [(1, 2), (3, 4), (5, 6)];
+    let retrival_erg = [(1, 2), (4, 3)];
+
+    let (precision, recall) = 
+        pso_train::calc_precision_recall(&retrival_erg, &reference); 
+    assert_approx_eq!(1.0 / 2.0, precision);
+    assert_approx_eq!(1.0 / 3.0, recall);
+}
+
+#[test]
+fn test_relex_fitness() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin is the capital of Germany. \
+        Paris is the capital of France. \
+        Rome is the capital of Italy. \
+        Lisbon is a city in Portugal.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let pair = |w1, w2| (env.get_argument_nrs(w1).unwrap(), env.get_argument_nrs(w2).unwrap());
+    let seed_pairs = [pair("Germany", "Berlin"), pair("France", "Paris")];
+    let reference_pairs = [pair("Germany", "Berlin"), pair("France", "Paris"),
+        pair("Italy", "Rome"), pair("Portugal", "Lisbon")];
+    let options = types::RelexOptions::default();
+
+    let fitness_fn = pso_train::RelexFitnessFn::new(
+        &seed_pairs, &reference_pairs, &options, &env);
+
+    let hyper_params = relex::RelexHyperParameter::from_vector(
+        relex::DEFAULT_RELEX_HYPER_PARAMETER.to_vector(), 0f64);
+    let (precision, recall) = fitness_fn.evaluate(&hyper_params);
+
+    assert_approx_eq!(1.0, precision);
+    assert_approx_eq!(3.0 / 4.0, recall);
+}
+
+#[test]
+fn test_relex_fitness_symmetric() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin is the capital of Germany. \
+        Paris is the capital of France. \
+        Rome is the capital of Italy. \
+        Lisbon is a city in Portugal.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    // the reference pairs are not in the order of the result pairs,
+    // which are ordered by word numbers (Berlin before Germany)
+    let pair = |w1, w2| (env.get_argument_nrs(w1).unwrap(), env.get_argument_nrs(w2).unwrap());
+    let seed_pairs = [pair("Germany", "Berlin"), pair("France", "Paris")];
+    let reference_pairs = [pair("Germany", "Berlin"), pair("France", "Paris"),
+        pair("Italy", "Rome"), pair("Portugal", "Lisbon")];
+    let options = types::RelexOptions {
+        symmetric: true,
+        ..Default::default()
+    };
+
+    let fitness_fn = pso_train::RelexFitnessFn::new(
+        &seed_pairs, &reference_pairs, &options, &env);
+
+    let hyper_params = relex::RelexHyperParameter::from_vector(
+        relex::DEFAULT_RELEX_HYPER_PARAMETER.to_vector(), 0f64);
+    let (precision, recall) = fitness_fn.evaluate(&hyper_params);
+
+    assert_approx_eq!(1.0, precision);
+    assert_approx_eq!(3.0 / 4.0, recall);
+}
+
+#[test]
+fn test_relex_fitness_multi_word() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin is the capital of Germany. \
+        Paris is the capital of France. \
+        Washington is the capital of the United States. \
+        Lisbon is a city in Portugal.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let pair = |w1, w2| (env.get_argument_nrs(w1).unwrap(), env.get_argument_nrs(w2).unwrap());
+    let seed_pairs = [pair("Germany", "Berlin"), pair("France", "Paris")];
+    let reference_pairs = [pair("Germany", "Berlin"), pair("France", "Paris"),
+        pair("United States", "Washington"), pair("Portugal", "Lisbon")];
+    let options = types::RelexOptions {
+        arguments: types::RelexArguments::Capitalised,
+        ..Default::default()
+    };
+
+    let fitness_fn = pso_train::RelexFitnessFn::new(
+        &seed_pairs, &reference_pairs, &options, &env);
+
+    let hyper_params = relex::RelexHyperParameter::from_vector(
+        relex::DEFAULT_RELEX_HYPER_PARAMETER.to_vector(), 0f64);
+    let (precision, recall) = fitness_fn.evaluate(&hyper_params);
+
+    // the pair of several words counts on both sides
+    assert_approx_eq!(1.0, precision);
+    assert_approx_eq!(3.0 / 4.0, recall);
+}
+
 #[test]
 fn test_read_reference() {
     let reference = "countries.txt";
@@ -20,3 +115,57 @@ fn test_read_reference() {
     let word_nrs = utils::read_word_file(&reference, &env);
     word_nrs.iter().for_each(|w_nr| println!("{}", w_nr));
 }
+
+#[test]
+fn test_evaluate_relex() {
+    let pattern = |infix: &str| types::ScoredPattern {
+        prefix: None,
+        infix: infix.split(' ').map(|w| w.to_owned()).collect(),
+        suffix: None,
+        order: false,
+        fitness: 10.0,
+        scope: String::new()
+    };
+    let pair = |w1: &str, w2: &str, patterns: Vec<usize>| types::ScoredPair {
+        w1: w1.to_owned(),
+        w2: w2.to_owned(),
+        fitness: 10.0,
+        patterns
+    };
+    let gold_pair = |w1: &str, w2: &str| types::GoldPair {
+        w1: w1.split('|').map(|w| w.to_owned()).collect(),
+        w2: w2.split('|').map(|w| w.to_owned()).collect()
+    };
+
+    let result = types::RelexResult {
+        patterns: vec!(pattern("is the capital of"), pattern("is a city in")),
+        pairs: vec!(
+            pair("Germany", "Berlin", vec!(0)),
+            pair("Deutschland", "Berlin", vec!(0)),
+            pair("Italy", "Rome", vec!(0, 1)),
+            pair("Portugal", "Porto", vec!(1))),
+        symmetric: false
+    };
+    let gold = [gold_pair("Germany|Deutschland", "Berlin"), gold_pair("Italy", "Rome"),
+        gold_pair("France", "Paris")];
+
+    let evaluation = pso_train::evaluate_relex(&result, &gold, &[1, 4]);
+
+    // both aliases of Germany are correct pairs, but count as one
+    // found gold pair
+    assert_approx_eq!(3.0 / 4.0, evaluation.precision);
+    assert_approx_eq!(2.0 / 3.0, evaluation.recall);
+    assert_approx_eq!(12.0 / 17.0, evaluation.f1);
+
+    assert_eq!(evaluation.precision_at_k.len(), 2);
+    assert_approx_eq!(1.0, evaluation.precision_at_k[0].1);
+    assert_approx_eq!(3.0 / 4.0, evaluation.precision_at_k[1].1);
+
+    // precision is the precision at k of all pairs
+    assert_eq!(evaluation.precision_at_k[1].0, result.pairs.len());
+    assert_approx_eq!(evaluation.precision, evaluation.precision_at_k[1].1);
+
+    assert_eq!((evaluation.patterns[0].pairs, evaluation.patterns[0].correct), (3, 3));
+    assert_eq!((evaluation.patterns[1].pairs, evaluation.patterns[1].correct), (2, 1));
+    assert_approx_eq!(0.5, evaluation.patterns[1].precision);
+}
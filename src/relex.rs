@@ -1,47 +1,252 @@
-use super::types::{AsyncLogger, DipreInput, EMPTY_WORD,
-WordNr, SentenceId, Env, WPair, Pattern}; 
-
-use log::{error, warn};
+use super::types::{AsyncLogger, DefaultLogger, DipreInput, CoupledDipreInput, RelationResult, EMPTY_WORD,
+WordNr, SentenceId, Env, WPair, Pattern, pos_slot, get_pos_slot,
+RelexResult, RelexOptions, RelexScoring, RelexArguments, ArgumentType, CoocInput,
+DiscoveryOptions, RelationCluster, PairExplanation, PatternEvidence, ScoredPattern, ScoredPair, WILDCARD, gap, get_gap,
+is_concrete_word}; 
+
+use super::conex::{self, DEFAULT_CONEX_HYPER_PARAMETER};
+use log::{info, error, warn};
+use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-// pattern was found for one or more wpairs 
-const PATTERN_WPAIR_BOOST: i16 = 10;
-// pattern (infix) was found one or more times
-const PATTERN_PATTERN_BOOST: i16 = -2;
-// pattern is to short MALUS
-const PATTERN_SHORT_SIZED_BOOST: i16 = -40;
-// pattern is medium sized
-const PATTERN_MEDIUM_SIZED_BOOST: i16 = 0;
-// pattern is to long MALUS
-const PATTERN_LONG_SIZED_BOOST: i16 = -40;
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RelexHyperParameter {
+    // pattern was found for one or more wpairs 
+    pub pattern_wpair_boost: f64,
+    // pattern (infix) was found one or more times
+    pub pattern_pattern_boost: f64,
+    // pattern is to short MALUS
+    pub pattern_short_sized_boost: f64,
+    // pattern is medium sized
+    pub pattern_medium_sized_boost: f64,
+    // pattern is to long MALUS
+    pub pattern_long_sized_boost: f64,
+
+    pub pattern_survivor_threshold: f64,
+
+    // word appears frequently in the global corpus
+    // needs to be dependent on the size of the corpus
+    pub wpair_word_global_frequency_boost_per_sentence: f64,
+
+    // wpair is identified over various patterns
+    pub wpair_pattern_boost: f64,
+
+    pub wpair_survivor_threshold: f64
+}
 
-const PATTERN_SURVIVOR_THRESHOLD: i16 = 12;
+impl RelexHyperParameter {
+    pub fn from_vector(v: Vec<f64>, fix_threshold: f64) -> RelexHyperParameter {
+        RelexHyperParameter {
+            pattern_wpair_boost: v[0],
+            pattern_pattern_boost: v[1],
+            pattern_short_sized_boost: v[2],
+            pattern_medium_sized_boost: v[3],
+            pattern_long_sized_boost: v[4],
+            pattern_survivor_threshold: fix_threshold,
+            wpair_word_global_frequency_boost_per_sentence: v[5],
+            wpair_pattern_boost: v[6],
+            wpair_survivor_threshold: fix_threshold
+        }
+    }
 
-const WPAIR_SURVIVOR_THRESHOLD: i16 = 20;
+    pub fn to_vector(&self) -> Vec<f64> {
+        vec![
+            self.pattern_wpair_boost,
+            self.pattern_pattern_boost,
+            self.pattern_short_sized_boost,
+            self.pattern_medium_sized_boost,
+            self.pattern_long_sized_boost,
+            // self.pattern_survivor_threshold,
+            self.wpair_word_global_frequency_boost_per_sentence,
+            self.wpair_pattern_boost,
+            // self.wpair_survivor_threshold
+        ]
+    }
+}
 
-// word appears frequently in the global corpus
-// needs to be dependent on the size of the corpus
-const WPAIR_WORD_GLOBAL_FREQUENCY_BOOST_PER_SENTENCE: f32 = -0.1; 
+impl Default for RelexHyperParameter {
+    fn default() -> RelexHyperParameter {
+        DEFAULT_RELEX_HYPER_PARAMETER
+    }
+}
+
+pub const DEFAULT_RELEX_HYPER_PARAMETER: RelexHyperParameter = RelexHyperParameter {
+    pattern_wpair_boost: 10.0,
+    pattern_pattern_boost: -2.0,
+    pattern_short_sized_boost: -40.0,
+    pattern_medium_sized_boost: 0.0,
+    pattern_long_sized_boost: -40.0,
+    pattern_survivor_threshold: 12.0,
+    wpair_word_global_frequency_boost_per_sentence: -0.1,
+    wpair_pattern_boost: 10.0,
+    wpair_survivor_threshold: 20.0
+};
+
+// sentences containing every one of the words
+pub fn find_sentences<'a>(mut words: impl Iterator<Item = &'a WordNr>, env: &Env)
+    -> HashSet<SentenceId> {
+
+    let mut sentence_ids: HashSet<SentenceId> = match words.next() {
+        Some(w) => env.inverted_idx.inverted_idx.get(w)
+            .expect("word not found in inverted index")
+            .to_owned(),
+        None => return HashSet::new()
+    };
 
-// wpair is identified over various patterns
-const WPAIR_PATTERN_BOOST: i16 = 10;
+    for w in words {
+        let idx_w = env.inverted_idx.inverted_idx.get(w)
+            .expect("word not found in inverted index");
+
+        sentence_ids = sentence_ids.intersection(idx_w)
+            .copied()
+            .collect::<HashSet<SentenceId>>();
+    }
 
+    sentence_ids
+}
+
+// sentences containing w1 and w2, arguments of several words
+// have to occur as a whole
 fn find_matches_wpair(wpair: &WPair, env: &Env) -> HashSet<SentenceId>{
-    let idx_w1 = env.inverted_idx.inverted_idx.get(&wpair.w1)
-        .expect("w1 not found in inverted index");
-    let idx_w2 = env.inverted_idx.inverted_idx.get(&wpair.w2)
-        .expect("w2 not found in inverted index");
+    let contains = |sent: &[WordNr], w: &[WordNr]| !w.is_empty()
+        && sent.windows(w.len()).any(|window| window == w);
+
+    find_sentences(wpair.w1.iter().chain(&wpair.w2), env).into_iter()
+        .filter(|s_id| {
+            let sent = env.get_sentence(s_id);
+            contains(sent, &wpair.w1) && contains(sent, &wpair.w2)
+        })
+        .collect()
+}
+
+// argument detection of RelexOptions.arguments with the
+// words and pos tags resolved to numbers
+pub struct ArgumentSpans {
+    pub arguments: RelexArguments,
+    pub max_len: usize,
+    skip: HashSet<WordNr>,
+    skip_pos: HashSet<WordNr>,
+    pos: HashSet<WordNr>
+}
+
+impl ArgumentSpans {
+    pub fn new(options: &RelexOptions, env: &Env) -> ArgumentSpans {
+        let tags = |tags: &[String]| tags.iter()
+            .filter_map(|t| env.pos.dict.get_opt_nr(t))
+            .collect();
+
+        ArgumentSpans {
+            arguments: options.arguments,
+            max_len: options.max_argument_len.max(1),
+            skip: options.argument_skip.iter()
+                .filter_map(|w| env.dict.get_opt_nr(w))
+                .collect(),
+            skip_pos: tags(&options.argument_skip_pos),
+            pos: tags(&options.argument_pos)
+        }
+    }
+
+    pub fn is_skipped(&self, sent: &[WordNr], s_id: &SentenceId, i: usize, env: &Env) -> bool {
+        self.skip.contains(&sent[i]) || (self.arguments == RelexArguments::Pos
+            && env.pos.get(s_id, i).is_some_and(|tag| self.skip_pos.contains(&tag)))
+    }
+
+    // the word may be part of an argument of several words
+    pub fn is_argument_word(&self, sent: &[WordNr], s_id: &SentenceId, i: usize, env: &Env)
+        -> bool {
+
+        match self.arguments {
+            RelexArguments::Token => false,
+            RelexArguments::Capitalised => env.dict.get_word(&sent[i])
+                .starts_with(char::is_uppercase),
+            RelexArguments::Pos => env.pos.get(s_id, i)
+                .is_some_and(|tag| self.pos.contains(&tag))
+        }
+    }
+
+    // the argument next to the infix, starting at position i and
+    // growing to the left (w1) or to the right (w2).
+    // Returns the range of the argument in the sentence.
+    pub fn argument(&self, sent: &[WordNr], s_id: &SentenceId, i: usize, left: bool, env: &Env)
+        -> Option<(usize, usize)> {
+
+        let next = |i: usize| if left { i.checked_sub(1) } 
+            else { Some(i + 1).filter(|i| *i < sent.len()) };
 
-    idx_w1.intersection(&idx_w2)
-        .copied()
-        .collect::<HashSet<SentenceId>>()
+        let mut first = Some(i).filter(|i| *i < sent.len());
+        while let Some(i) = first.filter(|i| self.is_skipped(sent, s_id, *i, env)) {
+            first = next(i);
+        }
+        let first = first?;
+
+        let mut last = first;
+        if self.is_argument_word(sent, s_id, first, env) {
+            let mut len = 1;
+            while let Some(i) = next(last) {
+                if len == self.max_len || !self.is_argument_word(sent, s_id, i, env) {
+                    break;
+                }
+                last = i;
+                len += 1;
+            }
+        }
+
+        Some((first.min(last), first.max(last) + 1))
+    }
+}
+
+// matches the infix at position pos of the sentence, returns the
+// position after the match. Gaps match as few words as possible.
+pub fn match_infix(infix: &[WordNr], sent: &[WordNr], pos: usize,
+    s_id: &SentenceId, env: &Env) -> Option<usize> {
+
+    let (w, rest) = match infix.split_first() {
+        Some(x) => x,
+        None => return Some(pos)
+    };
+
+    if let Some(max_len) = get_gap(*w) {
+        return (0..=max_len)
+            .take_while(|skip| pos + skip <= sent.len())
+            .find_map(|skip| match_infix(rest, sent, pos + skip, s_id, env));
+    }
+
+    if pos >= sent.len() {
+        return Option::None;
+    }
+
+    let matches = if *w == WILDCARD {
+        true
+    } else if let Some(tag) = get_pos_slot(*w) {
+        env.pos.get(s_id, pos) == Some(tag)
+    } else {
+        sent[pos] == *w
+    };
+
+    if matches {
+        match_infix(rest, sent, pos + 1, s_id, env)
+    } else {
+        Option::None
+    }
 }
 
 // fn find_matches_pattern <'a> (pattern: &Pattern, env: &'a Env) -> Vec<& 'a Vec<WordNr>> {
-fn find_matches_pattern(pattern: &Pattern, env: &Env) -> Vec<WPair> {
+fn find_matches_pattern(pattern: &Pattern, env: &Env, options: &RelexOptions) -> Vec<WPair> {
+    find_matches_pattern_in(pattern, env, options).into_iter()
+        .map(|(_, wpair)| wpair)
+        .collect()
+}
+
+// every match of the infix in a sentence yields a wpair, as long as it
+// satisfies the distance, context and document scope of the options,
+// together with the id of the sentence
+fn find_matches_pattern_in(pattern: &Pattern, env: &Env, options: &RelexOptions)
+    -> Vec<(SentenceId, WPair)> {
 
     let l = pattern.infix.len();
+    let spans = ArgumentSpans::new(options, env);
 
     // for empty infixes don't do anything
     if l < 1 {
@@ -58,16 +263,25 @@ fn find_matches_pattern(pattern: &Pattern, env: &Env) -> Vec<WPair> {
     // occurrence position - problem multiple occurrences in single sent. 
 
 
-    // take sentence_ids for first word of infix
+    // pos slots, wildcards and gaps are not part of the inverted
+    // index, only the concrete infix words reduce the search space
+    let mut concrete = pattern.infix.iter().filter(|w| is_concrete_word(**w));
+
+    let anchor = match concrete.next() {
+        Some(a) => a,
+        None => return Vec::new()
+    };
+
+    // take sentence_ids for the anchor word of infix
     let sentence_ids_infix_pos_0 = env.inverted_idx.inverted_idx
-        .get(&pattern.infix[0])
+        .get(anchor)
         .expect("infix word not found in inverted index");
 
     let mut sentence_ids: HashSet<SentenceId> = sentence_ids_infix_pos_0.to_owned();
 
-    for i in 1..l {
+    for w in concrete {
         let sentence_ids_infix_pos_i = env.inverted_idx.inverted_idx
-            .get(&pattern.infix[i])
+            .get(w)
             .expect("infix word not found in inverted index");
 
         sentence_ids = sentence_ids
@@ -76,51 +290,37 @@ fn find_matches_pattern(pattern: &Pattern, env: &Env) -> Vec<WPair> {
             .collect::<HashSet<SentenceId>>(); 
     }
 
-    // now search every sentence for the first infix word and look 
-    // the next infix.len() - 2 words.
+    // now search every sentence for all positions the infix
+    // matches at, w1 has to precede it
 
     sentence_ids.iter()
-        .map(|s_id| {
-            let sent = &env.sentences.sentences[*s_id as usize];
-            let mut infix_pos_0_idx = std::usize::MAX;
-
-            for (i, word) in sent.iter().enumerate() {
-                if *word == pattern.infix[0] {
-                    infix_pos_0_idx = i; 
-                    break;
-                }
-            }
-
-            if infix_pos_0_idx == std::usize::MAX {
-                panic!("find_matches_pattern: Could not find word {} in {:?}.",
-                       pattern.infix[0], sent);
-            }
-
-            (infix_pos_0_idx, sent, s_id)
-
-        })
-    .filter(|(infix_pos_0_idx, sent, _s_id)| {
-
-        for i in 1..pattern.infix.len() {
-            let p = infix_pos_0_idx + i;
-            if sent.len() == p || sent[p] != pattern.infix[i] {
-                return false;
-            }
-        }
-
-        true
+    .flat_map(|s_id| {
+        let sent = &env.sentences.sentences[*s_id as usize];
 
+        (1..sent.len())
+            .filter_map(move |start| match_infix(&pattern.infix, sent, start, s_id, env)
+                .map(|end| (start, end, sent, s_id)))
     })
-    .map(|(infix_pos_0_idx, sent, s_id)| {
-        let w1 = if infix_pos_0_idx == 0 {
-            EMPTY_WORD
+    .filter(|(start, end, _, _)| options.max_pair_distance == 0
+        || end - start <= options.max_pair_distance)
+    .filter(|(_, _, _, s_id)| options.document_scope == 0 || pattern.scope.is_empty()
+        || document_scope(s_id, env, options.document_scope).starts_with(&pattern.scope))
+    .filter_map(|(infix_pos_0_idx, idx2, sent, s_id)| {
+        let (w1_start, w1_end) = if spans.arguments == RelexArguments::Token {
+            (infix_pos_0_idx - 1, infix_pos_0_idx)
         } else {
-            sent[infix_pos_0_idx - 1]
+            spans.argument(sent, s_id, infix_pos_0_idx - 1, true, env)?
         };
 
-        let idx2 = infix_pos_0_idx + pattern.infix.len();
-        let w2 = if idx2 == sent.len() {
-            EMPTY_WORD
+        if options.match_prefix && pattern.prefix != EMPTY_WORD
+            && (w1_start < 1 || sent[w1_start - 1] != pattern.prefix) {
+            return Option::None;
+        }
+
+        let w2_range = if spans.arguments != RelexArguments::Token {
+            spans.argument(sent, s_id, idx2, false, env)
+        } else if idx2 == sent.len() {
+            Option::None
         } else {
             // special case THE
             if sent[idx2] == env.the {
@@ -132,69 +332,365 @@ fn find_matches_pattern(pattern: &Pattern, env: &Env) -> Vec<WPair> {
                     for i in 1..4 {
                         error!("{:?}", translate(&env.sentences.sentences[(s_id + i) as usize], &env));
                     }
-                    EMPTY_WORD
+                    Option::None
                 } else {
-                    sent[idx2 + 1]
+                    Some((idx2 + 1, idx2 + 2))
                 }
             } else {
-                sent[idx2]
+                Some((idx2, idx2 + 1))
             }
         };
 
-        if pattern.order {
-            WPair::new(w1, w2)
-        } else {
-            WPair::new(w2, w1)
+        let w1 = sent[w1_start..w1_end].to_vec();
+        let w2 = w2_range.map(|(start, end)| sent[start..end].to_vec()).unwrap_or_default();
+
+        if options.match_suffix && pattern.suffix != EMPTY_WORD
+            && w2_range.and_then(|(_, end)| sent.get(end)) != Some(&pattern.suffix) {
+            return Option::None;
         }
+
+        let (w1, w2) = if pattern.order { (w1, w2) } else { (w2, w1) };
+        let (w1, w2) = canonical_pair(w1, w2, options.symmetric);
+        Some((*s_id, WPair::new(w1, w2)))
     })
-    .filter(|WPair {w1, w2, ..}|
-            !(*w1 == EMPTY_WORD || *w2 == EMPTY_WORD) )
+    .filter(|(_, WPair {w1, w2, ..})| !(w1.is_empty() || w2.is_empty()))
         .collect()
 }
 
-fn extract_pattern(wpair: &WPair, sent: &[WordNr]) -> Pattern {
-
-    let mut idx1 = std::usize::MAX;
-    let mut idx2 = std::usize::MAX;
-    for (i, w) in sent.iter().enumerate() {
-        if *w == wpair.w1 {
-            idx1 = i;
-        } else if *w == wpair.w2 {
-            idx2 = i;
-        }
+// pairs of a symmetric relation are ordered by word numbers
+pub fn canonical_pair(w1: Vec<WordNr>, w2: Vec<WordNr>, symmetric: bool)
+    -> (Vec<WordNr>, Vec<WordNr>) {
+    if symmetric && w2 < w1 {
+        (w2, w1)
+    } else {
+        (w1, w2)
     }
+}
 
-    if idx1 == std::usize::MAX && idx2 == std::usize::MAX {
-        panic!("Either w1 {} or w2 {} not found in {:?}", wpair.w1, wpair.w2, sent);
-    }
+// patterns are identified by their infix and the order of w1 and w2,
+// patterns of a symmetric relation all have the same order
+type PatternKey = (Vec<WordNr>, bool);
+
+fn pattern_key(pattern: &Pattern) -> PatternKey {
+    (pattern.infix.clone(), pattern.order)
+}
 
-    let (idx1, idx2, order) = if idx1 < idx2 {
-        (idx1, idx2, true)
+// wpairs are identified by the words of both arguments
+type PairKey = (Vec<WordNr>, Vec<WordNr>);
+
+// the first len characters of the title of the sentence's document
+fn document_scope(s_id: &SentenceId, env: &Env, len: usize) -> String {
+    env.get_document(s_id)
+        .map(|d| d.title.chars().take(len).collect())
+        .unwrap_or_default()
+}
+
+// one pattern for every combination of w1 and w2 positions in the
+// sentence, which are at most max_pair_distance words apart.
+// Words of the infix tagged with one of the pos_generalise tags are
+// replaced by pos slots, at least one concrete word is kept. With
+// capitalised / pos arguments skipped words next to the arguments are
+// not part of the infix, they are skipped when matching.
+fn extract_patterns(wpair: &WPair, s_id: &SentenceId, env: &Env,
+    pos_generalise: &HashSet<WordNr>, options: &RelexOptions) -> Vec<Pattern> {
+
+    let sent = env.get_sentence(s_id);
+    let max_distance = options.max_pair_distance;
+    let spans = ArgumentSpans::new(options, env);
+
+    let scope = if options.document_scope > 0 {
+        document_scope(s_id, env, options.document_scope)
     } else {
-        (idx2, idx1, false)
+        String::new()
     };
 
-    let prefix = if idx1 == 0 { EMPTY_WORD } else { sent[idx1 - 1] };
+    // ranges of every occurrence of the argument
+    let positions = |w: &[WordNr]| (0..sent.len())
+        .filter(|i| sent[*i..].starts_with(w))
+        .map(|i| (i, i + w.len()))
+        .collect::<Vec<(usize, usize)>>();
+
+    let pos1 = positions(&wpair.w1);
+    let pos2 = positions(&wpair.w2);
+
+    // the words of an argument may occur apart from each other
+    if pos1.is_empty() || pos2.is_empty() {
+        return Vec::new();
+    }
+
+    let mut patterns = Vec::new();
+
+    for &r1 in &pos1 {
+        for &r2 in &pos2 {
+            // the ranges of the arguments (w1 == w2 has the same
+            // positions twice) and the infix between them
+            let ((start1, end1), (start2, end2), order) = if r1.1 <= r2.0 {
+                (r1, r2, true)
+            } else if r2.1 <= r1.0 {
+                (r2, r1, false)
+            } else {
+                continue;
+            };
+
+            let (mut a, mut b) = (end1, start2);
+            if spans.arguments != RelexArguments::Token {
+                while b - a > 1 && spans.is_skipped(sent, s_id, a, env) {
+                    a += 1;
+                }
+                while b - a > 1 && spans.is_skipped(sent, s_id, b - 1, env) {
+                    b -= 1;
+                }
+            }
+
+            if max_distance > 0 && b - a > max_distance {
+                continue;
+            }
 
-    let suffix = if idx2 < sent.len() - 1 {
-        sent[idx2 + 1]
-    } else { EMPTY_WORD }; 
+            let prefix = if start1 == 0 { EMPTY_WORD } else { sent[start1 - 1] };
 
-    Pattern::new(prefix, sent[idx1 + 1..idx2].to_vec(), suffix, order)
+            let suffix = if end2 < sent.len() {
+                sent[end2]
+            } else { EMPTY_WORD }; 
 
+            let mut infix = sent[a..b].to_vec();
+
+            if !pos_generalise.is_empty() {
+                let generalised: Vec<WordNr> = infix.iter().enumerate()
+                    .map(|(i, w)| match env.pos.get(s_id, a + i) {
+                        Some(tag) if pos_generalise.contains(&tag) => pos_slot(tag),
+                        _ => *w
+                    })
+                    .collect();
+
+                if generalised.iter().any(|w| get_pos_slot(*w).is_none()) {
+                    infix = generalised;
+                }
+            }
+
+            let mut pattern = Pattern::new(prefix, infix, suffix, order || options.symmetric);
+            pattern.scope = scope.clone();
+            patterns.push(pattern);
+        }
+    }
+
+    patterns
 }
 
 fn translate <'a> (sent: &[WordNr], env: &'a Env) -> Vec<&'a str>{
     sent.iter().map(|word_nr| env.dict.get_word(word_nr)).collect()
 }
 
-pub async fn do_relex<F: AsyncLogger>(
-    di: DipreInput, env: &Env, mut log: F) {
+// frequency boost of an argument, arguments of several words are as
+// frequent as their rarest word. Truncated toward zero like the former
+// integer fitness, so only words occurring in a large part of the
+// corpus are punished.
+pub fn argument_frequency_boost(w: &[WordNr], boost_per_sentence: f64, env: &Env) -> f64 {
+    (w.iter()
+        .map(|w| env.inverted_idx.inverted_idx.get(w)
+            .expect("w not found in inverted index")
+            .len())
+        .min()
+        .unwrap_or(0) as f64 * boost_per_sentence).trunc()
+}
+
+pub fn pattern_size_boost(infix_len: usize, hyper_params: &RelexHyperParameter) -> f64 {
+    if infix_len <= 1 {
+        hyper_params.pattern_short_sized_boost
+    } else if infix_len < 5 {
+        hyper_params.pattern_medium_sized_boost
+    } else {
+        hyper_params.pattern_long_sized_boost
+    }
+}
+
+// token level edit distance (levenshtein) of two infixes
+pub fn infix_edit_distance(a: &[WordNr], b: &[WordNr]) -> usize {
+    edit_distance_matrix(a, b)[a.len()][b.len()]
+}
+
+fn edit_distance_matrix(a: &[WordNr], b: &[WordNr]) -> Vec<Vec<usize>> {
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, d_0j) in d[0].iter_mut().enumerate() {
+        *d_0j = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + substitution);
+        }
+    }
+    d
+}
 
-    let wpairs: Vec<WPair> = di.pairs.iter()
-        .map(|(w1, w2)| WPair::new_str(w1, w2, env)).collect(); 
+// aligns two infixes, equal words are kept, runs of substituted words
+// become wildcards and runs of inserted / deleted words become a gap,
+// e.g. "is the capital of" and "is the largest city and capital of"
+// generalise to "is the *{0,3} capital of". Returns None if a gap
+// would exceed max_gap, the generalised infix would start or end
+// with a wildcard / gap or contains no concrete word anymore.
+pub fn generalise_infixes(a: &[WordNr], b: &[WordNr], max_gap: usize)
+    -> Option<Vec<WordNr>> {
+
+    let d = edit_distance_matrix(a, b);
+
+    // backtrace, collects the aligned words in reverse order
+    let mut aligned: Vec<(Option<WordNr>, Option<WordNr>)> = Vec::new();
+    let (mut i, mut j) = (a.len(), b.len());
+    while i > 0 || j > 0 {
+        let substitution = if i > 0 && j > 0 && a[i - 1] == b[j - 1] { 0 } else { 1 };
+        if i > 0 && j > 0 && d[i][j] == d[i - 1][j - 1] + substitution {
+            aligned.push((Some(a[i - 1]), Some(b[j - 1])));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && d[i][j] == d[i - 1][j] + 1 {
+            aligned.push((Some(a[i - 1]), Option::None));
+            i -= 1;
+        } else {
+            aligned.push((Option::None, Some(b[j - 1])));
+            j -= 1;
+        }
+    }
+    aligned.reverse();
+
+    let mut infix: Vec<WordNr> = Vec::new();
+    // words of a and b in the current run of differences
+    let (mut run_a, mut run_b) = (0, 0);
+
+    let close_run = |infix: &mut Vec<WordNr>, run_a: usize, run_b: usize| {
+        if run_a == run_b {
+            infix.extend(std::iter::repeat_n(WILDCARD, run_a));
+            true
+        } else if run_a.max(run_b) <= max_gap {
+            infix.push(gap(run_a.max(run_b)));
+            true
+        } else {
+            false
+        }
+    };
 
-    log.log(format!("finding matches for input {:?} wpairs.", wpairs)).await;
+    for (wa, wb) in aligned {
+        match (wa, wb) {
+            (Some(wa), Some(wb)) if wa == wb => {
+                if !close_run(&mut infix, run_a, run_b) {
+                    return Option::None;
+                }
+                run_a = 0;
+                run_b = 0;
+                infix.push(wa);
+            }
+            (wa, wb) => {
+                run_a += wa.is_some() as usize;
+                run_b += wb.is_some() as usize;
+            }
+        }
+    }
+    if !close_run(&mut infix, run_a, run_b) {
+        return Option::None;
+    }
+
+    let is_open_slot = |w: &WordNr| *w == WILDCARD || get_gap(*w).is_some();
+
+    if infix.first().is_none_or(is_open_slot)
+        || infix.last().is_none_or(is_open_slot)
+        || !infix.iter().any(|w| is_concrete_word(*w)) {
+        return Option::None;
+    }
+
+    Some(infix)
+}
+
+// merges every two patterns with similar infixes to a generalised
+// pattern, which is scored on the union of their occurrences
+fn generalise_patterns(
+    pattern_cache: &HashMap<PatternKey, Pattern>,
+    pattern_support: &HashMap<PatternKey, (HashSet<usize>, usize)>,
+    options: &RelexOptions,
+    hyper_params: &RelexHyperParameter) -> Vec<Pattern> {
+
+    let patterns: Vec<&Pattern> = pattern_cache.values().collect();
+
+    // generalised pattern -> pattern and the patterns merged into it
+    let mut generalised: HashMap<PatternKey, (Pattern, HashSet<PatternKey>)> =
+        HashMap::new();
+
+    for (i, a) in patterns.iter().enumerate() {
+        for b in patterns.iter().skip(i + 1) {
+            if a.order != b.order {
+                continue;
+            }
+
+            let len_diff = (a.infix.len() as isize - b.infix.len() as isize).unsigned_abs();
+            if len_diff > options.max_infix_edit_distance
+                || infix_edit_distance(&a.infix, &b.infix) > options.max_infix_edit_distance {
+                continue;
+            }
+
+            let infix = match generalise_infixes(&a.infix, &b.infix, options.max_gap) {
+                Some(infix) => infix,
+                None => continue
+            };
+
+            let (pattern, sources) = generalised.entry((infix.clone(), a.order))
+                .or_insert_with(|| {
+                    let mut p = Pattern::new(a.prefix, infix, a.suffix, a.order);
+                    p.scope = a.scope.clone();
+                    (p, HashSet::new())
+                });
+
+            for p in &[a, b] {
+                if pattern.prefix != p.prefix {
+                    pattern.prefix = EMPTY_WORD;
+                }
+                if pattern.suffix != p.suffix {
+                    pattern.suffix = EMPTY_WORD;
+                }
+                if pattern.scope != p.scope {
+                    pattern.scope.clear();
+                }
+                sources.insert(pattern_key(p));
+            }
+        }
+    }
+
+    generalised.into_values()
+        .map(|(mut pattern, sources)| {
+            let mut wpairs: HashSet<usize> = HashSet::new();
+            let mut occurrences = 0;
+            for key in sources {
+                let (infix_wpairs, infix_occurrences) = &pattern_support[&key];
+                wpairs.extend(infix_wpairs);
+                occurrences += infix_occurrences;
+            }
+
+            // gaps may match no word at all, they don't add to the size
+            let infix_len = pattern.infix.iter()
+                .filter(|w| get_gap(**w).is_none())
+                .count();
+
+            pattern.fitness = pattern_size_boost(infix_len, hyper_params)
+                + wpairs.len() as f64 * hyper_params.pattern_wpair_boost
+                + occurrences as f64 * hyper_params.pattern_pattern_boost;
+            pattern
+        })
+        .collect()
+}
+
+// learns patterns from the sentences containing the wpairs,
+// returns the patterns surviving the threshold
+async fn learn_patterns<F: AsyncLogger>(
+    wpairs: &[WPair],
+    pos_generalise: &HashSet<WordNr>,
+    options: &RelexOptions,
+    hyper_params: &RelexHyperParameter,
+    env: &Env,
+    log: &mut F) -> Vec<Pattern> {
+
+    log.log(format!("finding matches for {} wpairs.", wpairs.len())).await;
 
     let wpair_on_patterns: Vec<(&WPair, Vec<Pattern>)> =
         wpairs.iter()
@@ -202,22 +698,24 @@ pub async fn do_relex<F: AsyncLogger>(
             let sentence_ids = find_matches_wpair(wpair, &env);
 
             let patterns = sentence_ids.iter()
-                .map(|s_id| {
-                    let sent = &env.sentences.sentences[*s_id as usize];
-                    extract_pattern(wpair, sent)
-                }).collect::<Vec<Pattern>>();
+                .flat_map(|s_id| extract_patterns(wpair, s_id, env, pos_generalise,
+                    options))
+                .collect::<Vec<Pattern>>();
 
             (wpair, patterns)
         }).collect(); 
-    log.log("done finding matches for input wpairs.".to_owned()).await;
+    log.log("done finding matches for wpairs.".to_owned()).await;
 
     log.log("qualifying found matches to patterns.".to_owned()).await;
-    let mut pattern_cache: HashMap<Vec<WordNr>, Pattern> = HashMap::new();
+    let mut pattern_cache: HashMap<PatternKey, Pattern> = HashMap::new();
+    // wpairs (indices) and occurrence count of every pattern,
+    // needed to score generalised patterns
+    let mut pattern_support: HashMap<PatternKey, (HashSet<usize>, usize)> = HashMap::new();
 
     let mut pattern_count = 0;
-    for (_wpair, patterns) in wpair_on_patterns {
+    for (wpair_idx, (_wpair, patterns)) in wpair_on_patterns.into_iter().enumerate() {
 
-        let mut already_wpair_boosted: HashSet<Vec<WordNr>> = HashSet::new();
+        let mut already_wpair_boosted: HashSet<PatternKey> = HashSet::new();
 
         for pattern in patterns {
 
@@ -227,20 +725,11 @@ pub async fn do_relex<F: AsyncLogger>(
             // copy is created for every pattern, even if the
             // reference pattern exists in the cache
 
-            let mut p_ = (&pattern).clone();
+            let mut p_ = pattern.clone();
 
-            let mut p = pattern_cache.entry(pattern.infix)
+            let p = pattern_cache.entry(pattern_key(&pattern))
                 .or_insert({
-                    let infix_len = p_.infix.len();
-                    if infix_len <= 1 {
-                        p_.fitness += PATTERN_SHORT_SIZED_BOOST;
-                    }
-                    if infix_len > 1 && infix_len < 5 {
-                        p_.fitness += PATTERN_MEDIUM_SIZED_BOOST;
-                    }
-                    if infix_len >= 5 {
-                        p_.fitness += PATTERN_LONG_SIZED_BOOST;
-                    }
+                    p_.fitness += pattern_size_boost(p_.infix.len(), hyper_params);
                     p_
                 });
 
@@ -252,64 +741,196 @@ pub async fn do_relex<F: AsyncLogger>(
                 p.suffix = EMPTY_WORD;
             }
 
+            if p.scope != pattern.scope {
+                p.scope.clear();
+            }
+
             // boost for every wpair the pattern occured
             // -> intuition: pattern is able to identify a
             // more general range of wpairs - thus more suited
             // to the underlying relation. But boost only once
             // per wpair!
-            if ! already_wpair_boosted.contains(&p.infix){
-                p.fitness += PATTERN_WPAIR_BOOST;
+            let key = pattern_key(p);
+            if ! already_wpair_boosted.contains(&key){
+                p.fitness += hyper_params.pattern_wpair_boost;
                 // TODO try to find solution with pointer
                 // this is time consuming mem copy
-                already_wpair_boosted.insert(p.infix.clone());
+                already_wpair_boosted.insert(key.clone());
             }
 
             // boost for every time the pattern / infix
             // was found -> intuition: pattern is quite,
             // often seen. Could indicate that
             // pattern is overly general - minor malus.
-            p.fitness += PATTERN_PATTERN_BOOST;
+            p.fitness += hyper_params.pattern_pattern_boost;
 
+            let support = pattern_support.entry(key)
+                .or_insert_with(|| (HashSet::new(), 0));
+            support.0.insert(wpair_idx);
+            support.1 += 1;
         }
     }
     log.log("done qualifying found matches to patterns.".to_owned()).await; 
     log.log(format!("pattern count: {}", pattern_count)).await;
 
-    let patterns: Vec<&Pattern> = pattern_cache.values()
-        .filter(|pattern| pattern.fitness >= PATTERN_SURVIVOR_THRESHOLD)
+    if options.max_infix_edit_distance > 0 {
+        let generalised = generalise_patterns(&pattern_cache, &pattern_support,
+            options, hyper_params);
+        log.log(format!("{} generalised patterns from infixes within edit distance {}.",
+            generalised.len(), options.max_infix_edit_distance)).await;
+
+        for pattern in generalised {
+            pattern_cache.entry(pattern_key(&pattern)).or_insert(pattern);
+        }
+    }
+
+    let mut patterns: Vec<Pattern> = pattern_cache.into_values()
+        .filter(|pattern| pattern.fitness >= hyper_params.pattern_survivor_threshold)
         .collect();
+    patterns.sort_unstable_by(
+        |a, b| b.fitness.partial_cmp(&a.fitness)
+            .unwrap_or(std::cmp::Ordering::Equal));
     log.log(format!("{} patterns left after applying threshold fitness of {}.",
-             patterns.len(), PATTERN_SURVIVOR_THRESHOLD)).await;
+             patterns.len(), hyper_params.pattern_survivor_threshold)).await;
 
     for pattern in &patterns {
         pattern.println(&env);
     }
 
+    patterns
+}
+
+// Snowball pattern confidence positive / (positive + negative) of the
+// wpairs found by the pattern. Seed wpairs are positive, negative wpairs
+// and (for directed relations) wpairs with a seed's w1 but a different
+// w2 are negative.
+fn pattern_confidence(wpairs: &[WPair],
+    seed_w2s: &HashMap<Vec<WordNr>, HashSet<Vec<WordNr>>>,
+    negatives: &HashSet<PairKey>,
+    symmetric: bool) -> f64 {
+
+    let mut positive = 0;
+    let mut negative = 0;
+
+    for wpair in wpairs {
+        if negatives.contains(&wpair.key()) {
+            negative += 1;
+        } else if let Some(w2s) = seed_w2s.get(&wpair.w1) {
+            if w2s.contains(&wpair.w2) {
+                positive += 1;
+            } else if !symmetric {
+                negative += 1;
+            }
+        }
+    }
+
+    if positive + negative == 0 {
+        0.0
+    } else {
+        positive as f64 / (positive + negative) as f64
+    }
+}
+
+// finds the wpairs of every pattern. With boost scoring patterns are
+// penalised for every negative wpair they find and dropped if they fall
+// below the threshold (or at the first negative wpair with
+// drop_negative_patterns). With confidence scoring the fitness of the
+// pattern is replaced by its confidence.
+async fn match_patterns<F: AsyncLogger>(
+    patterns: Vec<Pattern>,
+    seeds: &[WPair],
+    negatives: &HashSet<PairKey>,
+    options: &RelexOptions,
+    hyper_params: &RelexHyperParameter,
+    env: &Env,
+    log: &mut F) -> (Vec<Pattern>, Vec<Vec<WPair>>) {
+
     log.log(format!("finding new wpairs for surviving patterns (fitness >= {}).",
-    PATTERN_SURVIVOR_THRESHOLD)).await;
+    hyper_params.pattern_survivor_threshold)).await;
+
+    let mut seed_w2s: HashMap<Vec<WordNr>, HashSet<Vec<WordNr>>> = HashMap::new();
+    for wpair in seeds {
+        seed_w2s.entry(wpair.w1.clone()).or_default().insert(wpair.w2.clone());
+    }
 
-    let pattern_on_wpairs = patterns.iter()
+    let (patterns, pattern_on_wpairs): (Vec<Pattern>, Vec<Vec<WPair>>) = patterns.into_iter()
         .map(|pattern| {
-            let wpairs = find_matches_pattern(&pattern, &env);
+            let wpairs = find_matches_pattern(&pattern, env, options);
             (pattern, wpairs)
-        });
+        })
+        .filter_map(|(mut pattern, wpairs)| {
+            if options.scoring == RelexScoring::Confidence {
+                pattern.fitness = pattern_confidence(&wpairs, &seed_w2s, negatives,
+                    options.symmetric);
+
+                return if pattern.fitness >= options.min_pattern_confidence {
+                    Some((pattern, wpairs))
+                } else {
+                    None
+                };
+            }
+
+            let negative_count = wpairs.iter()
+                .filter(|wpair| negatives.contains(&wpair.key()))
+                .count();
+
+            if negative_count == 0 {
+                return Some((pattern, wpairs));
+            }
+
+            pattern.fitness -= negative_count as f64 * options.negative_pattern_penalty;
+
+            if options.drop_negative_patterns
+                || pattern.fitness < hyper_params.pattern_survivor_threshold {
+                None
+            } else {
+                Some((pattern, wpairs))
+            }
+        })
+        .unzip();
     log.log("done finding new wpairs for surviving patterns.".to_owned()).await;
 
+    if options.scoring == RelexScoring::Confidence {
+        log.log(format!("{} patterns left after applying confidence of {}.",
+            patterns.len(), options.min_pattern_confidence)).await;
+    } else if !negatives.is_empty() {
+        log.log(format!("{} patterns left after checking {} negative wpairs.",
+            patterns.len(), negatives.len())).await;
+    }
+
+    (patterns, pattern_on_wpairs)
+}
+
+// finds and qualifies new wpairs for the patterns, returns the wpairs
+// surviving the threshold sorted by fitness, each with the indices
+// of the patterns which found it
+#[allow(clippy::too_many_arguments)]
+async fn find_wpairs<F: AsyncLogger>(
+    patterns: &[Pattern],
+    pattern_on_wpairs: Vec<Vec<WPair>>,
+    negatives: &HashSet<PairKey>,
+    argument_types: &ArgumentTypes,
+    options: &RelexOptions,
+    hyper_params: &RelexHyperParameter,
+    env: &Env,
+    log: &mut F) -> Vec<(WPair, Vec<usize>)> {
+
+    debug_assert_eq!(patterns.len(), pattern_on_wpairs.len());
+
     log.log("qualifying found wpairs.".to_owned()).await;
-    let mut wpair_cache: HashMap<(WordNr, WordNr), WPair> = HashMap::new();
+    let mut wpair_cache: HashMap<PairKey, (WPair, Vec<usize>)> = HashMap::new();
 
     let wpair_word_frequency_boost =
-        WPAIR_WORD_GLOBAL_FREQUENCY_BOOST_PER_SENTENCE / env.sentences.sentences.len() as f32;  
+        hyper_params.wpair_word_global_frequency_boost_per_sentence
+        / env.sentences.sentences.len() as f64;  
 
     log.log(format!("wpair_word_frequency_boost = {}", wpair_word_frequency_boost)).await;
 
     let mut wpair_count = 0;
 
-    log.log("qualifying found wpairs".to_owned()).await;
+    for (pattern_idx, wpairs) in pattern_on_wpairs.into_iter().enumerate() {
 
-    for (_pattern, wpairs) in pattern_on_wpairs {
-
-        let mut already_pattern_boosted: HashSet<(WordNr, WordNr)> = HashSet::new();
+        let mut already_pattern_boosted: HashSet<PairKey> = HashSet::new();
 
         for wpair in wpairs {
 
@@ -317,8 +938,8 @@ pub async fn do_relex<F: AsyncLogger>(
 
             let mut wp_ = wpair.clone();
 
-            let wp = wpair_cache.entry((wpair.w1, wpair.w2))
-                .or_insert({
+            let (wp, supporting_patterns) = wpair_cache.entry(wpair.key())
+                .or_insert_with(|| {
                     // maybe the most dificult part to boost is
                     // the global term frequency. Intuition here
                     // is that if a wpair contains a very frequent term
@@ -327,44 +948,24 @@ pub async fn do_relex<F: AsyncLogger>(
                     // this in relation to the overall corpus size, since
                     // this term seem to be overly general
 
-                    let calc_freq_boost = |w| {
-                        env.inverted_idx.inverted_idx.get(w)
-                            .expect("w not found in inverted index")
-                            .len() as f32 * wpair_word_frequency_boost 
-                    };
-
-                    let w1_freq_boost = calc_freq_boost(&wp_.w1); 
-                    let w2_freq_boost = calc_freq_boost(&wp_.w2); 
-
-                    // this can get seriously wrong if the numbers outgrow
-                    // i16::MIN, but if this happens our fitness score
-                    // is messed up anyways
-                    let save_cast = |wn_freq_boost, w| {
-                        if wn_freq_boost < std::i16::MIN as f32 {
-                            warn!("Word frequency boost outmaxed by {}",
-                                     env.dict.get_word(w));
-                            std::i16::MIN
-                        } else {
-                            // save cast now
-                            wn_freq_boost as i16
-                        }
-                    };
-
-                    let w1_freq_boost = save_cast(w1_freq_boost, &wp_.w1);
-                    let w2_freq_boost = save_cast(w2_freq_boost, &wp_.w2);
+                    let w1_freq_boost = argument_frequency_boost(&wp_.w1,
+                        wpair_word_frequency_boost, env); 
+                    let w2_freq_boost = argument_frequency_boost(&wp_.w2,
+                        wpair_word_frequency_boost, env); 
 
                     wp_.fitness += w1_freq_boost + w2_freq_boost; 
 
-                    wp_
+                    (wp_, Vec::new())
                 });
 
 
             // boost positively if a single wpair is defined
             // by more than one pattern.
 
-            let tmp_wpair = (wp.w1, wp.w2);
+            let tmp_wpair = wp.key();
             if ! already_pattern_boosted.contains(&tmp_wpair){
-                wp.fitness += WPAIR_PATTERN_BOOST;
+                wp.fitness += hyper_params.wpair_pattern_boost;
+                supporting_patterns.push(pattern_idx);
                 // TODO try to find solution with pointer
                 // this is time consuming mem copy
                 already_pattern_boosted.insert(tmp_wpair);
@@ -376,34 +977,719 @@ pub async fn do_relex<F: AsyncLogger>(
     log.log("done qualifying found wpairs.".to_owned()).await; 
     log.log(format!("wpair count: {}", wpair_count)).await;
 
-    let mut wpairs: Vec<&WPair> = wpair_cache.values()
-        .filter(|wpair| wpair.fitness >= WPAIR_SURVIVOR_THRESHOLD)
+    // with confidence scoring the fitness of a wpair is the noisy-or
+    // of the confidences of the patterns which found it
+    let threshold = if options.scoring == RelexScoring::Confidence {
+        for (wpair, pattern_idxs) in wpair_cache.values_mut() {
+            wpair.fitness = 1.0 - pattern_idxs.iter()
+                .map(|i| 1.0 - patterns[*i].fitness)
+                .product::<f64>();
+        }
+        options.min_pair_confidence
+    } else {
+        hyper_params.wpair_survivor_threshold
+    };
+
+    if argument_types.is_typed() {
+        wpair_cache.retain(|_, (wpair, _)| {
+            let violations = argument_types.violations(wpair, options.symmetric);
+            wpair.fitness -= violations as f64 * options.argument_type_penalty;
+            violations == 0 || !options.drop_untyped_pairs
+        });
+        log.log(format!("{} wpairs left after checking argument types.",
+            wpair_cache.len())).await;
+    }
+
+    let mut wpairs: Vec<(WPair, Vec<usize>)> = wpair_cache.into_values()
+        .filter(|(wpair, _)| wpair.fitness >= threshold)
+        .filter(|(wpair, _)| !negatives.contains(&wpair.key()))
         .collect();
     log.log(format!("{} wpairs left after applying threshold fitness of {}.",
-             wpairs.len(), WPAIR_SURVIVOR_THRESHOLD)).await;
+             wpairs.len(), threshold)).await;
 
     log.log("sorting wpairs by fitness.".to_owned()).await;
     wpairs.sort_unstable_by(
-        |a, b| i16::cmp(&a.fitness, &b.fitness).reverse());
+        |(a, _), (b, _)| b.fitness.partial_cmp(&a.fitness)
+            .unwrap_or(std::cmp::Ordering::Equal));
     log.log("done sorting wpairs by fitness.".to_owned()).await;
 
-    log.log("building a map of w1 to vec w2".to_owned()).await;
+    wpairs
+}
+
+// the words allowed as w1 and w2, None: any word
+struct ArgumentTypes {
+    w1: Option<HashSet<Vec<WordNr>>>,
+    w2: Option<HashSet<Vec<WordNr>>>
+}
 
-    let mut w1_on_w2s: HashMap<&WordNr, Vec<&WordNr>> = HashMap::new();
-    for wpair in wpairs {
-        w1_on_w2s.entry(&wpair.w1)
-            .or_insert_with(Vec::new)
-            .push(&wpair.w2);
+impl ArgumentTypes {
+    fn new(options: &RelexOptions, env: &Env) -> ArgumentTypes {
+        ArgumentTypes {
+            w1: options.w1_type.as_ref().map(|t| argument_type_words(t, env)),
+            w2: options.w2_type.as_ref().map(|t| argument_type_words(t, env))
+        }
+    }
+
+    fn is_typed(&self) -> bool {
+        self.w1.is_some() || self.w2.is_some()
+    }
+
+    // count of the arguments outside their type, wpairs of
+    // symmetric relations are checked in both orders
+    fn violations(&self, wpair: &WPair, symmetric: bool) -> usize {
+        let outside = |words: &Option<HashSet<Vec<WordNr>>>, argument: &Vec<WordNr>|
+            match words {
+                Some(words) => !(words.contains(argument) || argument.last()
+                    .is_some_and(|w| words.contains(&vec!(*w)))) as usize,
+                None => 0
+            };
+
+        let violations = outside(&self.w1, &wpair.w1) + outside(&self.w2, &wpair.w2);
+
+        if symmetric {
+            violations.min(outside(&self.w1, &wpair.w2) + outside(&self.w2, &wpair.w1))
+        } else {
+            violations
+        }
+    }
+}
+
+// the listed words, the conex seed terms and the words conex finds for them
+fn argument_type_words(argument_type: &ArgumentType, env: &Env) -> HashSet<Vec<WordNr>> {
+    let mut words: HashSet<Vec<WordNr>> = argument_type.words.iter()
+        .filter_map(|w| {
+            let argument = env.get_argument_nrs(w);
+            if argument.is_none() {
+                warn!("Argument type word \"{}\" not found in dictionary.", w);
+            }
+            argument
+        })
+        .collect();
+
+    if !argument_type.conex_seeds.is_empty() {
+        let cooc_input = CoocInput { set: argument_type.conex_seeds.clone() };
+        let hyper_params = argument_type.conex_hyper_parameter.as_ref()
+            .unwrap_or(&DEFAULT_CONEX_HYPER_PARAMETER);
+
+        let found = conex::do_conex(&cooc_input, hyper_params, env);
+        let found = if argument_type.conex_pos_filter.is_empty() {
+            found
+        } else {
+            conex::filter_by_pos(&found, &argument_type.conex_pos_filter, env)
+        };
+        info!("conex found {} words for the argument type of {:?}.",
+            found.len(), argument_type.conex_seeds);
+
+        words.extend(conex::cooc_input_to_word_nr_set(&cooc_input, env).into_iter()
+            .chain(found)
+            .map(|w| vec!(w)));
+    }
+
+    words
+}
+
+// state of a relation during bootstrapping
+struct RelationRun {
+    name: String,
+    seeds: Vec<WPair>,
+    known: HashSet<PairKey>,
+    negatives: HashSet<PairKey>,
+    // patterns learned from the original seeds
+    seed_patterns: HashSet<PatternKey>,
+    // options of the relation, see do_relex_coupled
+    options: RelexOptions,
+    types: ArgumentTypes,
+    patterns: Vec<Pattern>,
+    wpairs: Vec<(WPair, Vec<usize>)>
+}
+
+impl RelationRun {
+    fn new(name: &str, pairs: &[(String, String)], negative_pairs: &[(String, String)],
+        options: RelexOptions, env: &Env) -> RelationRun {
+
+        let symmetric = options.symmetric;
+
+        let mut seeds: Vec<WPair> = Vec::new();
+        for (w1, w2) in pairs {
+            let wpair = WPair::new_str(w1, w2, env);
+            let (w1, w2) = canonical_pair(wpair.w1, wpair.w2, symmetric);
+            // (w1, w2) and (w2, w1) are the same seed of a symmetric relation
+            if !seeds.iter().any(|s| s.w1 == w1 && s.w2 == w2) {
+                seeds.push(WPair::new(w1, w2));
+            }
+        }
+
+        let known = seeds.iter()
+            .map(|wpair| wpair.key())
+            .collect();
+
+        // negative wpairs with unknown words can't be found anyway
+        let negatives = negative_pairs.iter()
+            .filter_map(|(w1, w2)| Some((env.get_argument_nrs(w1)?, env.get_argument_nrs(w2)?)))
+            .map(|(w1, w2)| canonical_pair(w1, w2, symmetric))
+            .collect();
+
+        RelationRun {
+            name: name.to_owned(),
+            seeds,
+            known,
+            negatives,
+            seed_patterns: HashSet::new(),
+            types: ArgumentTypes::new(&options, env),
+            options,
+            patterns: Vec::new(),
+            wpairs: Vec::new()
+        }
+    }
+}
+
+// keeps every pattern and every wpair only in the relation
+// which scored it best, ties are dropped from all relations
+fn enforce_exclusivity<T: Eq + std::hash::Hash + Clone>(
+    claims: Vec<Vec<(T, f64)>>) -> Vec<HashSet<T>> {
+
+    let mut best: HashMap<T, (usize, f64, bool)> = HashMap::new();
+    for (r, relation_claims) in claims.iter().enumerate() {
+        for (key, fitness) in relation_claims {
+            let entry = best.entry(key.clone()).or_insert((r, *fitness, false));
+            if entry.0 != r {
+                if *fitness > entry.1 {
+                    *entry = (r, *fitness, false);
+                } else if (*fitness - entry.1).abs() < f64::EPSILON {
+                    entry.2 = true;
+                }
+            }
+        }
+    }
+
+    (0..claims.len())
+        .map(|r| claims[r].iter()
+            .filter(|(key, _)| best.get(key)
+                .is_some_and(|(best_r, _, tie)| *best_r == r && !tie))
+            .map(|(key, _)| key.clone())
+            .collect())
+        .collect()
+}
+
+// DIPRE bootstrapping: every round learns patterns from the seeds,
+// finds new wpairs with them and promotes the best new wpairs to seeds.
+// With several relations (coupled bootstrapping) the seeds of the other
+// relations are negatives and every pattern and wpair is only kept by
+// the relation which scored it best.
+async fn bootstrap<F: AsyncLogger>(
+    runs: &mut [RelationRun], options: &RelexOptions,
+    hyper_params: &RelexHyperParameter, env: &Env, log: &mut F) {
+
+    let pos_generalise: HashSet<WordNr> = options.pos_generalise.iter()
+        .filter_map(|t| env.pos.dict.get_opt_nr(t))
+        .collect();
+
+    if !options.pos_generalise.is_empty() {
+        log.log(format!("generalising infix words tagged {:?}.", options.pos_generalise)).await;
+        if !env.has_pos() {
+            warn!("No part-of-speech annotations loaded, infixes are not generalised.");
+        }
+    }
+
+    let coupled = runs.len() > 1;
+
+    for round in 1..=options.iterations.max(1) {
+
+        let mut matched: Vec<Vec<Vec<WPair>>> = Vec::new();
+        let mut negatives: Vec<HashSet<PairKey>> = Vec::new();
+
+        for r in 0..runs.len() {
+            let symmetric = runs[r].options.symmetric;
+            let mut run_negatives = runs[r].negatives.clone();
+            for (o, other) in runs.iter().enumerate() {
+                if o != r {
+                    for (w1, w2) in &other.known {
+                        run_negatives.insert(canonical_pair(w1.clone(), w2.clone(), symmetric));
+                        // the other relation doesn't care about the order
+                        if other.options.symmetric {
+                            run_negatives.insert(canonical_pair(w2.clone(), w1.clone(), symmetric));
+                        }
+                    }
+                }
+            }
+
+            let run = &mut runs[r];
+            let options = &run.options;
+
+            log.log(format!("starting round {} of {} with {} seed wpairs{}.",
+                round, options.iterations.max(1), run.seeds.len(),
+                if coupled { format!(" for relation {}", run.name) } else { String::new() }
+            )).await;
+
+            let learned = learn_patterns(&run.seeds, &pos_generalise, options,
+                hyper_params, env, log).await;
+
+            let (patterns, pattern_on_wpairs) = match_patterns(learned, &run.seeds,
+                &run_negatives, options, hyper_params, env, log).await;
+
+            run.patterns = patterns;
+            matched.push(pattern_on_wpairs);
+            negatives.push(run_negatives);
+        }
+
+        if coupled {
+            let keep = enforce_exclusivity(runs.iter()
+                .map(|run| run.patterns.iter()
+                    .map(|p| (pattern_key(p), p.fitness))
+                    .collect())
+                .collect());
+
+            for ((run, pattern_on_wpairs), keep) in runs.iter_mut()
+                .zip(matched.iter_mut()).zip(keep) {
+
+                let (patterns, wpairs): (Vec<Pattern>, Vec<Vec<WPair>>) =
+                    std::mem::take(&mut run.patterns).into_iter()
+                    .zip(std::mem::take(pattern_on_wpairs))
+                    .filter(|(p, _)| keep.contains(&pattern_key(p)))
+                    .unzip();
+                log.log(format!("relation {}: {} exclusive patterns.",
+                    run.name, patterns.len())).await;
+                run.patterns = patterns;
+                *pattern_on_wpairs = wpairs;
+            }
+        }
+
+        for ((run, pattern_on_wpairs), run_negatives) in runs.iter_mut()
+            .zip(matched).zip(negatives.iter()) {
+
+            if round == 1 {
+                run.seed_patterns = run.patterns.iter().map(pattern_key).collect();
+            }
+
+            run.wpairs = find_wpairs(&run.patterns, pattern_on_wpairs, run_negatives,
+                &run.types, &run.options, hyper_params, env, log).await;
+        }
+
+        if coupled {
+            let keep = enforce_exclusivity(runs.iter()
+                .map(|run| run.wpairs.iter()
+                    .map(|(wp, _)| (wp.key(), wp.fitness))
+                    .collect())
+                .collect());
+
+            for (run, keep) in runs.iter_mut().zip(keep) {
+                run.wpairs.retain(|(wp, _)| keep.contains(&wp.key()));
+            }
+        }
+
+        if round == options.iterations.max(1) {
+            break;
+        }
+
+        let mut promoted_count = 0;
+
+        for run in runs.iter_mut() {
+            // semantic drift control: only wpairs which are still
+            // found by patterns of the original seeds are promoted
+            let promoted: Vec<WPair> = run.wpairs.iter()
+                .filter(|(wpair, _)| !run.known.contains(&wpair.key()))
+                .filter(|(_, pattern_idxs)| pattern_idxs.iter()
+                    .filter(|i| run.seed_patterns.contains(&pattern_key(&run.patterns[**i])))
+                    .count() >= options.min_seed_pattern_support)
+                .take(if options.max_new_pairs_per_round == 0 { usize::MAX }
+                    else { options.max_new_pairs_per_round })
+                .map(|(wpair, _)| WPair::new(wpair.w1.clone(), wpair.w2.clone()))
+                .collect();
+
+            log.log(format!("round {}: promoting {} new seed wpairs{}.",
+                round, promoted.len(),
+                if coupled { format!(" for relation {}", run.name) } else { String::new() }
+            )).await;
+
+            promoted_count += promoted.len();
+
+            for wpair in promoted {
+                wpair.println(env);
+                run.known.insert(wpair.key());
+                run.seeds.push(wpair);
+            }
+        }
+
+        if promoted_count == 0 {
+            log.log("no new seed wpairs, stopping.".to_owned()).await;
+            break;
+        }
+    }
+}
+
+pub async fn do_relex<F: AsyncLogger>(
+    di: DipreInput, hyper_params: &RelexHyperParameter, env: &Env, mut log: F)
+    -> RelexResult {
+
+    let mut runs = vec!(RelationRun::new("", &di.pairs, &di.negative_pairs,
+        di.options.clone(), env));
+
+    log.log(format!("input wpairs {:?}.", runs[0].seeds)).await;
+
+    if !di.negative_pairs.is_empty() {
+        log.log(format!("{} of {} negative wpairs found in dict.",
+            runs[0].negatives.len(), di.negative_pairs.len())).await;
+    }
+
+    bootstrap(&mut runs, &di.options, hyper_params, env, &mut log).await;
+
+    let run = &runs[0];
+
+    log.log(format!("done, {} patterns and {} wpairs found.",
+        run.patterns.len(), run.wpairs.len())).await;
+
+    relex_result(&run.patterns, &run.wpairs, run.options.symmetric, env)
+}
+
+// coupled bootstrapping of several relations, see bootstrap
+pub async fn do_relex_coupled<F: AsyncLogger>(
+    ci: CoupledDipreInput, hyper_params: &RelexHyperParameter, env: &Env, mut log: F)
+    -> Vec<RelationResult> {
+
+    let mut runs: Vec<RelationRun> = ci.relations.iter()
+        .map(|r| RelationRun::new(&r.name, &r.pairs, &r.negative_pairs,
+            RelexOptions {
+                symmetric: r.symmetric.unwrap_or(ci.options.symmetric),
+                w1_type: r.w1_type.clone().or_else(|| ci.options.w1_type.clone()),
+                w2_type: r.w2_type.clone().or_else(|| ci.options.w2_type.clone()),
+                ..ci.options.clone()
+            }, env))
+        .collect();
+
+    for run in &runs {
+        log.log(format!("relation {}: input wpairs {:?}.", run.name, run.seeds)).await;
     }
 
-    log.log("done building a map of w1 to vec w2".to_owned()).await;
+    bootstrap(&mut runs, &ci.options, hyper_params, env, &mut log).await;
 
-    for (w1, w2s) in w1_on_w2s {
-        log.log(format!("\"{}\":", env.dict.get_word(w1))).await;
-        for w in w2s.iter().map(|w| env.dict.get_word(w)) {
-            log.log(format!("\t \"{}\"", w)).await;
+    let mut results = Vec::new();
+    for run in &runs {
+        log.log(format!("relation {}: done, {} patterns and {} wpairs found.",
+            run.name, run.patterns.len(), run.wpairs.len())).await;
+
+        results.push(RelationResult {
+            name: run.name.clone(),
+            result: relex_result(&run.patterns, &run.wpairs, run.options.symmetric, env)
+        });
+    }
+    results
+}
+
+fn relex_result(patterns: &[Pattern], wpairs: &[(WPair, Vec<usize>)], symmetric: bool,
+    env: &Env) -> RelexResult {
+
+    RelexResult {
+        patterns: patterns.iter()
+            .map(|pattern| ScoredPattern::new(pattern, env))
+            .collect(),
+        pairs: wpairs.iter()
+            .map(|(wpair, pattern_idxs)| ScoredPair {
+                w1: env.get_argument(&wpair.w1),
+                w2: env.get_argument(&wpair.w2),
+                fitness: wpair.fitness,
+                patterns: pattern_idxs.clone()
+            })
+            .collect(),
+        symmetric
+    }
+}
+
+// finds wpairs with a saved pattern set, e.g. learned on another
+// corpus. Patterns keep their saved fitness, patterns with words
+// unknown to this corpus are skipped.
+pub async fn apply_patterns<F: AsyncLogger>(
+    saved: &[ScoredPattern], options: &RelexOptions,
+    hyper_params: &RelexHyperParameter, env: &Env, mut log: F) -> RelexResult {
+
+    let patterns: Vec<Pattern> = saved.iter()
+        .filter_map(|p| {
+            let pattern = p.to_pattern(env);
+            if pattern.is_none() {
+                warn!("skipping pattern {:?}, infix not found in dict.", p.infix);
+            }
+            pattern
+        })
+        .collect();
+
+    log.log(format!("applying {} of {} saved patterns.", 
+        patterns.len(), saved.len())).await;
+
+    let pattern_on_wpairs: Vec<Vec<WPair>> = patterns.iter()
+        .map(|pattern| find_matches_pattern(pattern, env, options))
+        .collect();
+
+    let wpairs = find_wpairs(&patterns, pattern_on_wpairs, &HashSet::new(),
+        &ArgumentTypes::new(options, env), options, hyper_params, env, &mut log).await;
+
+    relex_result(&patterns, &wpairs, options.symmetric, env)
+}
+
+// entities of a sentence for relation discovery: capitalised words
+// (words tagged with argument_pos for pos arguments) of up to
+// max_argument_len words, words only capitalised at the start of
+// the sentence (their lower case form is known) are no entities
+fn find_entities(sent: &[WordNr], s_id: &SentenceId, spans: &ArgumentSpans, env: &Env)
+    -> Vec<(usize, usize)> {
+
+    let is_entity_word = |i: usize| {
+        if spans.arguments == RelexArguments::Pos {
+            return spans.is_argument_word(sent, s_id, i, env);
+        }
+        let word = env.dict.get_word(&sent[i]);
+        word.starts_with(char::is_uppercase)
+            && (i > 0 || env.dict.get_opt_nr(&word.to_lowercase()).is_none())
+    };
+
+    let max_len = if spans.arguments == RelexArguments::Token { 1 } else { spans.max_len };
+
+    let mut entities = Vec::new();
+    let mut i = 0;
+    while i < sent.len() {
+        if is_entity_word(i) {
+            let mut end = i + 1;
+            while end < sent.len() && end - i < max_len && is_entity_word(end) {
+                end += 1;
+            }
+            entities.push((i, end));
+            i = end;
+        } else {
+            i += 1;
         }
     }
+
+    entities
 }
 
+// patterns with the entity pairs they connect, and all pairs of the cluster
+type Cluster = (Vec<(Pattern, HashSet<PairKey>)>, HashSet<PairKey>);
+
+fn jaccard(a: &HashSet<PairKey>, b: &HashSet<PairKey>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        0.0
+    } else {
+        a.intersection(b).count() as f64 / union as f64
+    }
+}
+
+// open relation discovery without seed pairs: collects the patterns
+// between neighbouring frequent entities of every sentence, scores
+// them like learn_patterns and clusters them by the entity pairs
+// they connect. Clusters are sorted by fitness.
+pub async fn discover_relations<F: AsyncLogger>(
+    discovery: &DiscoveryOptions, options: &RelexOptions,
+    hyper_params: &RelexHyperParameter, env: &Env, mut log: F) -> Vec<RelationCluster> {
+
+    let spans = ArgumentSpans::new(options, env);
+    let pos_generalise: HashSet<WordNr> = options.pos_generalise.iter()
+        .filter_map(|t| env.pos.dict.get_opt_nr(t))
+        .collect();
+
+    log.log("finding entities.".to_owned()).await;
+    let sentence_entities: Vec<(SentenceId, Vec<(usize, usize)>)> =
+        (0..env.sentences.sentences.len() as SentenceId)
+        .map(|s_id| (s_id, find_entities(env.get_sentence(&s_id), &s_id, &spans, env)))
+        .collect();
+
+    // count of sentences containing the entity
+    let mut entity_frequency: HashMap<&[WordNr], usize> = HashMap::new();
+    for (s_id, entities) in &sentence_entities {
+        let sent = env.get_sentence(s_id);
+        let unique: HashSet<&[WordNr]> = entities.iter()
+            .map(|(a, b)| &sent[*a..*b])
+            .collect();
+        for entity in unique {
+            *entity_frequency.entry(entity).or_insert(0) += 1;
+        }
+    }
+    log.log(format!("{} entities, {} in at least {} sentences.", entity_frequency.len(),
+        entity_frequency.values().filter(|f| **f >= discovery.min_entity_frequency).count(),
+        discovery.min_entity_frequency)).await;
+
+    // pattern, the entity pairs it connects and its occurrence count
+    let mut pattern_cache: HashMap<PatternKey, (Pattern, HashSet<PairKey>, usize)> =
+        HashMap::new();
+
+    for (s_id, entities) in &sentence_entities {
+        let sent = env.get_sentence(s_id);
+        let frequent: Vec<&[WordNr]> = entities.iter()
+            .map(|(a, b)| &sent[*a..*b])
+            .filter(|entity| entity_frequency[entity] >= discovery.min_entity_frequency)
+            .collect();
+
+        for pair in frequent.windows(2) {
+            if pair[0] == pair[1] {
+                continue;
+            }
 
+            let wpair = WPair::new(pair[0].to_vec(), pair[1].to_vec());
+
+            for pattern in extract_patterns(&wpair, s_id, env, &pos_generalise, options) {
+                if pattern.infix.is_empty() {
+                    continue;
+                }
+
+                let (p, pairs, occurrences) = pattern_cache.entry(pattern_key(&pattern))
+                    .or_insert_with(|| (pattern.clone(), HashSet::new(), 0));
+
+                if p.prefix != pattern.prefix {
+                    p.prefix = EMPTY_WORD;
+                }
+                if p.suffix != pattern.suffix {
+                    p.suffix = EMPTY_WORD;
+                }
+
+                pairs.insert(wpair.key());
+                *occurrences += 1;
+            }
+        }
+    }
+    log.log(format!("{} patterns between entities.", pattern_cache.len())).await;
+
+    let mut patterns: Vec<(Pattern, HashSet<PairKey>)> = pattern_cache.into_values()
+        .filter(|(_, pairs, _)| pairs.len() >= discovery.min_pattern_pairs)
+        .map(|(mut pattern, pairs, occurrences)| {
+            pattern.fitness = pattern_size_boost(pattern.infix.len(), hyper_params)
+                + pairs.len() as f64 * hyper_params.pattern_wpair_boost
+                + occurrences as f64 * hyper_params.pattern_pattern_boost;
+            (pattern, pairs)
+        })
+        .filter(|(pattern, _)| pattern.fitness >= hyper_params.pattern_survivor_threshold)
+        .collect();
+    patterns.sort_unstable_by(
+        |(a, _), (b, _)| b.fitness.partial_cmp(&a.fitness)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| pattern_key(a).cmp(&pattern_key(b))));
+    log.log(format!("{} patterns connect at least {} entity pairs.",
+        patterns.len(), discovery.min_pattern_pairs)).await;
+
+    // greedy clustering, the strongest patterns found the clusters
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for (pattern, pairs) in patterns {
+        let best = clusters.iter().enumerate()
+            .map(|(i, (_, cluster_pairs))| (i, jaccard(&pairs, cluster_pairs)))
+            .filter(|(_, similarity)| *similarity >= discovery.min_cluster_similarity)
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        match best {
+            Some((i, _)) => {
+                clusters[i].1.extend(pairs.iter().cloned());
+                clusters[i].0.push((pattern, pairs));
+            },
+            None => clusters.push((vec!((pattern, pairs.clone())), pairs))
+        }
+    }
+    log.log(format!("{} relation clusters.", clusters.len())).await;
+
+    let mut clusters: Vec<RelationCluster> = clusters.into_iter()
+        .map(|(patterns, cluster_pairs)| {
+            let mut pairs: Vec<(PairKey, Vec<usize>)> = cluster_pairs.into_iter()
+                .map(|pair| {
+                    let pattern_idxs = patterns.iter().enumerate()
+                        .filter(|(_, (_, pairs))| pairs.contains(&pair))
+                        .map(|(i, _)| i)
+                        .collect();
+                    (pair, pattern_idxs)
+                })
+                .collect();
+            pairs.sort_unstable_by(|(a, a_idxs), (b, b_idxs)|
+                b_idxs.len().cmp(&a_idxs.len()).then_with(|| a.cmp(b)));
+
+            RelationCluster {
+                fitness: patterns.iter().map(|(p, _)| p.fitness).sum(),
+                patterns: patterns.iter()
+                    .map(|(pattern, _)| ScoredPattern::new(pattern, env))
+                    .collect(),
+                pairs: pairs.into_iter()
+                    .take(discovery.max_examples)
+                    .map(|((w1, w2), pattern_idxs)| ScoredPair {
+                        w1: env.get_argument(&w1),
+                        w2: env.get_argument(&w2),
+                        fitness: pattern_idxs.len() as f64,
+                        patterns: pattern_idxs
+                    })
+                    .collect()
+            }
+        })
+        .collect();
+    clusters.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness)
+        .unwrap_or(std::cmp::Ordering::Equal));
+
+    clusters
+}
+
+// the patterns of the result which found the pair, each with the
+// sentences it matched the pair in. None if the result does not
+// contain the pair.
+pub fn explain_pair(result: &RelexResult, w1: &str, w2: &str, options: &RelexOptions,
+    env: &Env, max_sentences: usize) -> Option<PairExplanation> {
+
+    let pair = result.pairs.iter()
+        .find(|p| (p.w1 == w1 && p.w2 == w2)
+            || (result.symmetric && p.w1 == w2 && p.w2 == w1))?;
+    let key = (env.get_argument_nrs(&pair.w1)?, env.get_argument_nrs(&pair.w2)?);
+
+    // symmetric pairs are matched in their canonical order
+    let options = RelexOptions {
+        symmetric: result.symmetric,
+        ..options.clone()
+    };
+
+    let patterns = pair.patterns.iter()
+        .map(|i| {
+            let scored = &result.patterns[*i];
+            let mut s_ids: Vec<SentenceId> = scored.to_pattern(env)
+                .map(|pattern| find_matches_pattern_in(&pattern, env, &options))
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(_, wpair)| wpair.key() == key)
+                .map(|(s_id, _)| s_id)
+                .collect();
+            s_ids.sort_unstable();
+            s_ids.dedup();
+
+            PatternEvidence {
+                pattern: scored.clone(),
+                sentence_count: s_ids.len(),
+                sentences: s_ids.iter()
+                    .take(max_sentences)
+                    .map(|s_id| env.get_argument(env.get_sentence(s_id)))
+                    .collect()
+            }
+        })
+        .collect();
+
+    Some(PairExplanation {
+        w1: pair.w1.clone(),
+        w2: pair.w2.clone(),
+        fitness: pair.fitness,
+        patterns
+    })
+}
+
+pub fn write_patterns(patterns: &[ScoredPattern], file_name: &str) {
+    let json = serde_json::to_string_pretty(patterns)
+        .expect("Could not serialize patterns to JSON String");
+    std::fs::write(file_name, json)
+        .unwrap_or_else(|_| panic!("Unable to write pattern file \"{}\".", file_name));
+}
+
+pub fn read_patterns(file_name: &str) -> Vec<ScoredPattern> {
+    let json = std::fs::read_to_string(file_name)
+        .unwrap_or_else(|_| panic!("Unable to open pattern file \"{}\".", file_name));
+    serde_json::from_str(&json)
+        .unwrap_or_else(|_| panic!("Unable to read pattern file \"{}\".", file_name))
+}
+
+// runs do_relex to completion on the current thread,
+// used by the fitness functions of the training
+pub fn do_relex_sync(di: DipreInput, hyper_params: &RelexHyperParameter, env: &Env)
+    -> RelexResult {
+
+    let mut runtime = tokio::runtime::Builder::new()
+        .basic_scheduler()
+        .build()
+        .expect("Unable to start tokio runtime.");
+
+    runtime.block_on(do_relex(di, hyper_params, env, DefaultLogger::new()))
+}
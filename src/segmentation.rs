@@ -0,0 +1,141 @@
+use log::info;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Result};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+// Unicode word boundaries (UAX #29) split scripts which are written
+// without spaces into single characters. For these scripts a dictionary
+// based segmentation is applied instead.
+pub fn is_non_space_script(c: char) -> bool {
+    matches!(c as u32,
+        // CJK Unified Ideographs (+ Extension A, Compatibility)
+        0x4E00..=0x9FFF | 0x3400..=0x4DBF | 0xF900..=0xFAFF |
+        // CJK Unified Ideographs Extension B - F
+        0x20000..=0x2EBEF |
+        // Hiragana, Katakana, Katakana Phonetic Extensions
+        0x3040..=0x309F | 0x30A0..=0x30FF | 0x31F0..=0x31FF |
+        // Halfwidth Katakana
+        0xFF66..=0xFF9F |
+        // Thai, Lao, Myanmar, Khmer
+        0x0E00..=0x0EFF | 0x1000..=0x109F | 0x1780..=0x17FF)
+}
+
+pub fn is_non_space_token(token: &str) -> bool {
+    token.chars().all(is_non_space_script)
+}
+
+// forward maximum matching against a lexicon, words not
+// found in the lexicon fall back to single characters
+pub struct MaxMatchSegmenter {
+    lexicon: HashSet<String>,
+    // length of the longest lexicon entry in chars
+    max_len: usize
+}
+
+impl MaxMatchSegmenter {
+    pub fn new(words: Vec<String>) -> MaxMatchSegmenter {
+        let max_len = words.iter()
+            .map(|w| w.chars().count())
+            .max()
+            .unwrap_or(1);
+
+        MaxMatchSegmenter {
+            lexicon: words.into_iter().collect(),
+            max_len
+        }
+    }
+
+    // one entry per line, only the first whitespace separated
+    // column is used, so frequency annotated lexicons work as well
+    pub fn from_file(file_name: &str) -> MaxMatchSegmenter {
+        let f = File::open(file_name)
+            .unwrap_or_else(|_| panic!("Unable to open lexicon file \"{}\".", file_name));
+
+        let words: Vec<String> = BufReader::new(f).lines()
+            .collect::<Result<Vec<String>>>()
+            .unwrap_or_else(|_| panic!("Unable to read lexicon file \"{}\".", file_name))
+            .iter()
+            .filter_map(|l| l.split_whitespace().next())
+            .map(|w| w.to_owned())
+            .collect();
+
+        info!("{} lexicon entries read from \"{}\".", words.len(), file_name);
+
+        MaxMatchSegmenter::new(words)
+    }
+
+    pub fn segment<'a>(&self, s: &'a str) -> Vec<&'a str> {
+        // byte offsets of every char plus the end of the string
+        let offsets: Vec<usize> = s.char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(s.len()))
+            .collect();
+
+        let n = offsets.len() - 1;
+        let mut words = Vec::new();
+        let mut i = 0;
+
+        while i < n {
+            let mut j = std::cmp::min(i + self.max_len, n);
+
+            while j > i + 1 && !self.lexicon.contains(&s[offsets[i]..offsets[j]]) {
+                j -= 1;
+            }
+
+            words.push(&s[offsets[i]..offsets[j]]);
+            i = j;
+        }
+
+        words
+    }
+}
+
+pub enum Tokenizer {
+    // unicode word boundaries only
+    Unicode,
+    // unicode word boundaries, runs of non-space script
+    // characters are segmented with the lexicon
+    MaxMatch(MaxMatchSegmenter)
+}
+
+impl Tokenizer {
+    pub fn tokenize<'a>(&self, sent: &'a str) -> Vec<&'a str> {
+        match self {
+            Tokenizer::Unicode => sent.split_word_bounds()
+                .filter(|word| *word != " ")
+                .collect(),
+
+            Tokenizer::MaxMatch(segmenter) => {
+                let mut words = Vec::new();
+                // byte range of the current non-space script run
+                let mut run: Option<(usize, usize)> = None;
+
+                for (i, word) in sent.split_word_bound_indices() {
+                    if is_non_space_token(word) {
+                        run = match run {
+                            Some((start, _)) => Some((start, i + word.len())),
+                            None => Some((i, i + word.len()))
+                        };
+                        continue;
+                    }
+
+                    if let Some((start, end)) = run.take() {
+                        words.append(&mut segmenter.segment(&sent[start..end]));
+                    }
+
+                    if word != " " {
+                        words.push(word);
+                    }
+                }
+
+                if let Some((start, end)) = run {
+                    words.append(&mut segmenter.segment(&sent[start..end]));
+                }
+
+                words
+            }
+        }
+    }
+}
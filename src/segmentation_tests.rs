@@ -0,0 +1,78 @@
+use super::*;
+use segmentation::{Tokenizer, MaxMatchSegmenter};
+
+fn segmenter() -> MaxMatchSegmenter {
+    MaxMatchSegmenter::new(vec![
+        "北京".to_string(),
+        "北京大学".to_string(),
+        "大学".to_string(),
+        "首都".to_string(),
+        "中国".to_string(),
+        "東京".to_string()
+    ])
+}
+
+#[test]
+fn test_max_match(){
+    let s = segmenter();
+
+    assert_eq!(s.segment("北京大学"), vec!["北京大学"]);
+    assert_eq!(s.segment("北京是中国的首都"),
+        vec!["北京", "是", "中国", "的", "首都"]);
+    assert_eq!(s.segment(""), Vec::<&str>::new());
+}
+
+#[test]
+fn test_tokenizer_mixed_scripts(){
+    let t = Tokenizer::MaxMatch(segmenter());
+
+    assert_eq!(t.tokenize("北京是中国的首都。"),
+        vec!["北京", "是", "中国", "的", "首都", "。"]);
+    assert_eq!(t.tokenize("Berlin und 東京は首都"),
+        vec!["Berlin", "und", "東京", "は", "首都"]);
+}
+
+#[test]
+fn test_tokenizer_unicode(){
+    let t = Tokenizer::Unicode;
+
+    assert_eq!(t.tokenize("Berlin is the capital of Germany."),
+        vec!["Berlin", "is", "the", "capital", "of", "Germany", "."]);
+}
+
+#[test]
+fn test_relex_max_match_arguments(){
+    let tokenizer = Tokenizer::MaxMatch(MaxMatchSegmenter::new(
+        ["中国", "北京", "日本", "东京", "韩国", "首尔", "首都"].iter()
+            .map(|w| w.to_string())
+            .collect()));
+
+    let mut env = Env::new();
+    xml::add_text_to_env("中国的首都是北京。日本的首都是东京。韩国的首都是首尔。",
+        &mut env, &tokenizer);
+
+    // dictionary words of several ideographs, not split into single ones
+    let beijing = env.dict.get_nr("北京");
+    assert_eq!(env.get_argument_nrs("北京"), Some(vec![beijing]));
+    assert_eq!(env.get_argument_nrs("东京北京"), Some(vec![env.dict.get_nr("东京"), beijing]));
+    assert_eq!(env.get_argument_nrs("北京大学"), None);
+
+    // no spaces between words of non-space scripts
+    let argument = [env.dict.get_nr("首都"), beijing];
+    assert_eq!(env.get_argument(&argument), "首都北京");
+    assert_eq!(env.get_argument_nrs("首都北京"), Some(argument.to_vec()));
+
+    let di = types::DipreInput::new(vec!(("中国", "北京"), ("日本", "东京")));
+    // a single pattern, so a single pattern suffices for a pair
+    let hyper_params = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 10.0,
+        ..relex::RelexHyperParameter::default()
+    };
+
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &hyper_params, &env, types::DefaultLogger::new()));
+
+    assert_eq!(result.patterns.len(), 1);
+    assert_eq!(result.patterns[0].infix, vec!("的", "首都", "是"));
+    assert!(result.pairs.iter().any(|p| p.w1 == "韩国" && p.w2 == "首尔"));
+}
@@ -1,6 +1,7 @@
 use log::{info, error};
-use super::types::{Env, DipreInput, AsyncLogger};
-use super::relex::do_relex;
+use super::types::{Env, DipreInput, CoupledDipreInput, TupleInput, AsyncLogger};
+use super::relex::{do_relex, do_relex_coupled, DEFAULT_RELEX_HYPER_PARAMETER};
+use super::nary::do_nary;
 use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -16,11 +17,10 @@ struct ALogger {
     conn_valid: bool
 }
 
-#[async_trait]
-impl AsyncLogger for ALogger {
-    async fn log(&mut self, mut s: String) {
-        info!("{}", s);
-        s.push_str("\n");
+impl ALogger {
+    // one line of the response body
+    async fn send_line(&mut self, mut s: String) {
+        s.push('\n');
         if self.conn_valid {
             if let Err(e) = self.sender.send_data(s.into()).await {
                 error!("logging endpoint failed, marking connection as invalid: {}", e);
@@ -30,24 +30,50 @@ impl AsyncLogger for ALogger {
     }
 }
 
+// borrowed by the run, so the result can be sent after the progress
+#[async_trait]
+impl AsyncLogger for &mut ALogger {
+    async fn log(&mut self, s: String) {
+        info!("{}", s);
+        self.send_line(s).await;
+    }
+}
+
 async fn handle_client(_req: Request<Body>, env: Arc<Env>) 
     -> Result<Response<Body>, Error> {
 
     let body = hyper::body::to_bytes(_req).await;
     if let Ok(body) = body {
         let di: Result<DipreInput, _> = serde_json::from_slice(&body);
-        if let Ok(di) = di {
+        // several relations are given as {"relations": [...]},
+        // n-ary seed tuples as {"tuples": [...]}
+        let ci: Result<CoupledDipreInput, _> = serde_json::from_slice(&body);
+        let ti: Result<TupleInput, _> = serde_json::from_slice(&body);
+        if di.is_ok() || ci.is_ok() || ti.is_ok() {
+            // the response body streams the progress messages line by
+            // line, the last line is the RelexResult (one per relation,
+            // or the TupleResult) as JSON
             let (sender, body) = Body::channel();
 
-            let logger = ALogger {
-                sender,
-                conn_valid: true
-            };
-
-            let env = env.clone();
-
             let calc = async move {
-                do_relex(di, env.as_ref(), logger).await;
+                let mut logger = ALogger {
+                    sender,
+                    conn_valid: true
+                };
+
+                let result = match (di, ci, ti) {
+                    (Ok(di), _, _) => do_relex(di, &DEFAULT_RELEX_HYPER_PARAMETER, 
+                        env.as_ref(), &mut logger).await.serialize(),
+                    (_, Ok(ci), _) => serde_json::to_string(&do_relex_coupled(ci,
+                        &DEFAULT_RELEX_HYPER_PARAMETER, env.as_ref(), &mut logger).await)
+                        .expect("Could not serialize RelationResult to JSON String"),
+                    (_, _, Ok(ti)) => serde_json::to_string(&do_nary(ti,
+                        &DEFAULT_RELEX_HYPER_PARAMETER, env.as_ref(), &mut logger).await)
+                        .expect("Could not serialize TupleResult to JSON String"),
+                    _ => unreachable!()
+                };
+
+                logger.send_line(result).await;
             };
 
             tokio::spawn(calc);
@@ -81,9 +107,8 @@ async fn handle_client(_req: Request<Body>, env: Arc<Env>)
 // }
 
 //SERVER
-pub async fn run_server(env: Env){
-   
-    let port = 23233;
+pub async fn run_server(env: Env, port: u16){
+
     let env = Arc::new(env);
     // let senv = Arc::new(ServerEnv::new()); 
 
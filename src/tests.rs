@@ -1,3 +1,4 @@
+use assert_approx_eq::assert_approx_eq;
 use super::*;
 
 #[test]
@@ -10,6 +11,327 @@ fn test_conex_config() {
         .unwrap();
 }
 
+#[test]
+fn test_relex_config() {
+    let config_file = "relex.toml";
+
+    let config: RelexConfig = 
+        toml::from_str(&read_to_string(config_file)
+            .unwrap_or_else(|_| panic!("Unable to open file \"{}\".", config_file)))
+        .unwrap();
+
+    assert_eq!(config.seed_pairs[0], ("Germany".to_owned(), "Berlin".to_owned()));
+    assert_eq!(config.hyper_parameter.to_vector(),
+        relex::DEFAULT_RELEX_HYPER_PARAMETER.to_vector());
+    assert_eq!(config.options.iterations, 1);
+}
+
+fn capital_env() -> Env {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin is the capital of Germany. \
+        Paris is the capital of France. \
+        Rome is the capital of Italy. \
+        Madrid is the capital of Spain. \
+        Lisbon is a city in Portugal.", &mut env, &segmentation::Tokenizer::Unicode);
+    env
+}
+
+#[test]
+fn test_relex_result() {
+    let env = capital_env();
+    let di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+
+    let hp = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 5.0,
+        ..Default::default()
+    };
+
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &hp, &env, types::DefaultLogger::new()));
+
+    assert_eq!(result.patterns.len(), 1);
+    assert_eq!(result.patterns[0].infix, vec!("is", "the", "capital", "of"));
+    assert!(!result.patterns[0].order);
+
+    let pairs: Vec<(&str, &str)> = result.pairs.iter()
+        .map(|p| (p.w1.as_str(), p.w2.as_str()))
+        .collect();
+    assert_eq!(pairs.len(), 4);
+    assert!(pairs.contains(&("Italy", "Rome")));
+    assert!(pairs.contains(&("Spain", "Madrid")));
+    assert!(!pairs.contains(&("Portugal", "Lisbon")));
+    assert!(result.pairs.iter().all(|p| p.patterns == vec!(0)));
+}
+
+#[test]
+fn test_generalise_infixes() {
+    let mut env = Env::new();
+    let words = |s: &str, env: &mut Env| s.split(' ')
+        .map(|w| env.add_word(w)).collect::<Vec<WordNr>>();
+
+    let a = words("is the capital of", &mut env);
+    let b = words("is the largest city and capital of", &mut env);
+    let c = words("is the seat of", &mut env);
+
+    assert_eq!(relex::infix_edit_distance(&a, &b), 3);
+
+    let ab = relex::generalise_infixes(&a, &b, 3).unwrap();
+    assert_eq!(ab.iter().map(|w| env.get_infix_word(w)).collect::<Vec<String>>(),
+        vec!("is", "the", "*{0,3}", "capital", "of"));
+    assert_eq!(relex::generalise_infixes(&a, &b, 2), Option::None);
+
+    let ac = relex::generalise_infixes(&a, &c, 3).unwrap();
+    assert_eq!(ac.iter().map(|w| env.get_infix_word(w)).collect::<Vec<String>>(),
+        vec!("is", "the", "*", "of"));
+
+    // generalised infixes have to start and end with a word
+    let d = words("capital of", &mut env);
+    let e = words("capital city", &mut env);
+    assert_eq!(relex::generalise_infixes(&d, &e, 3), Option::None);
+}
+
+#[test]
+fn test_relex_generalised_patterns() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin is the capital of Germany. \
+        Paris is the largest city and capital of France. \
+        Rome is the beautiful capital of Italy.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let mut di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+    di.options.max_infix_edit_distance = 3;
+
+    let hp = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 5.0,
+        ..Default::default()
+    };
+
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &hp, &env, types::DefaultLogger::new()));
+
+    // neither exact infix is found for two seed pairs
+    assert_eq!(result.patterns.len(), 1);
+    assert_eq!(result.patterns[0].infix, vec!("is", "the", "*{0,3}", "capital", "of"));
+
+    assert!(result.pairs.iter().any(|p| p.w1 == "Italy" && p.w2 == "Rome"));
+}
+
+#[test]
+fn test_relex_negative_pairs() {
+    let env = capital_env();
+
+    let hp = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 5.0,
+        ..Default::default()
+    };
+
+    let mut di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+    di.negative_pairs = vec!(("Italy".to_owned(), "Rome".to_owned()));
+    di.options.negative_pattern_penalty = 2.0;
+
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &hp, &env, types::DefaultLogger::new()));
+
+    // penalised by one negative pair, the pattern still survives
+    assert_eq!(result.patterns.len(), 1);
+    assert_eq!(result.patterns[0].fitness, 14.0);
+    assert!(result.pairs.iter().any(|p| p.w1 == "Spain" && p.w2 == "Madrid"));
+    assert!(!result.pairs.iter().any(|p| p.w1 == "Italy" && p.w2 == "Rome"));
+
+    let mut di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+    di.negative_pairs = vec!(("Italy".to_owned(), "Rome".to_owned()));
+    di.options.negative_pattern_penalty = 2.0;
+    di.options.drop_negative_patterns = true;
+
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &hp, &env, types::DefaultLogger::new()));
+
+    assert!(result.patterns.is_empty());
+    assert!(result.pairs.is_empty());
+}
+
+#[test]
+fn test_relex_confidence_scoring() {
+    let mut env = capital_env();
+    xml::add_text_to_env("Bonn is the capital of Germany.", 
+        &mut env, &segmentation::Tokenizer::Unicode);
+
+    let mut di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+    di.options.scoring = types::RelexScoring::Confidence;
+    di.options.min_pattern_confidence = 0.5;
+    di.options.min_pair_confidence = 0.5;
+
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &relex::DEFAULT_RELEX_HYPER_PARAMETER, 
+            &env, types::DefaultLogger::new()));
+
+    // two seed pairs found, (Germany, Bonn) conflicts with (Germany, Berlin)
+    assert_eq!(result.patterns.len(), 1);
+    assert!((result.patterns[0].fitness - 2.0 / 3.0).abs() < 1e-9);
+
+    let rome = result.pairs.iter().find(|p| p.w1 == "Italy").unwrap();
+    assert_eq!(rome.w2, "Rome");
+    assert!((rome.fitness - 2.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_relex_every_occurrence() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin is the capital of Germany and Paris is the capital of France. \
+        Rome is the capital of Italy and Madrid is the capital of Spain.",
+        &mut env, &segmentation::Tokenizer::Unicode);
+
+    let hp = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 5.0,
+        ..Default::default()
+    };
+
+    let di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &hp, &env, types::DefaultLogger::new()));
+
+    assert_eq!(result.patterns.len(), 1);
+    assert!(result.pairs.iter().any(|p| p.w1 == "Italy" && p.w2 == "Rome"));
+    assert!(result.pairs.iter().any(|p| p.w1 == "Spain" && p.w2 == "Madrid"));
+
+    // the infix is longer than the allowed distance
+    let mut di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+    di.options.max_pair_distance = 3;
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &hp, &env, types::DefaultLogger::new()));
+
+    assert!(result.patterns.is_empty());
+}
+
+fn run_relex_capitals(env: &Env, options: types::RelexOptions) -> Vec<(String, String)> {
+    let hp = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 5.0,
+        ..Default::default()
+    };
+
+    let mut di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+    di.options = options;
+
+    tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &hp, env, types::DefaultLogger::new()))
+        .pairs.into_iter()
+        .map(|p| (p.w1, p.w2))
+        .collect()
+}
+
+#[test]
+fn test_relex_context_and_scope() {
+    let mut env = Env::new();
+    let tokenizer = segmentation::Tokenizer::Unicode;
+    xml::add_document_to_env("1", "Capitals: Germany", 
+        "In 2000 Berlin is the capital of Germany.", &mut env, &tokenizer);
+    xml::add_document_to_env("2", "Capitals: France", 
+        "In 2000 Paris is the capital of France.", &mut env, &tokenizer);
+    xml::add_document_to_env("3", "Capitals: Spain", 
+        "In 2000 Madrid is the capital of Spain.", &mut env, &tokenizer);
+    xml::add_document_to_env("4", "Cities: Italy", 
+        "Today Rome is the capital of Italy.", &mut env, &tokenizer);
+
+    let italy = ("Italy".to_owned(), "Rome".to_owned());
+    let spain = ("Spain".to_owned(), "Madrid".to_owned());
+
+    let pairs = run_relex_capitals(&env, types::RelexOptions::default());
+    assert!(pairs.contains(&italy) && pairs.contains(&spain));
+
+    // "Today" is not the learned prefix "2000"
+    let pairs = run_relex_capitals(&env, types::RelexOptions {
+        match_prefix: true, ..Default::default() });
+    assert!(!pairs.contains(&italy) && pairs.contains(&spain));
+
+    // both suffixes are "."
+    let pairs = run_relex_capitals(&env, types::RelexOptions {
+        match_suffix: true, ..Default::default() });
+    assert!(pairs.contains(&italy) && pairs.contains(&spain));
+
+    // learned in documents titled "Capitals:..."
+    let pairs = run_relex_capitals(&env, types::RelexOptions {
+        document_scope: 9, ..Default::default() });
+    assert!(!pairs.contains(&italy) && pairs.contains(&spain));
+}
+
+#[test]
+fn test_relex_apply_saved_patterns() {
+    let env = capital_env();
+    let hp = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 5.0,
+        ..Default::default()
+    };
+
+    let di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+    let mut rt = tokio::runtime::Runtime::new().unwrap();
+    let result = rt.block_on(relex::do_relex(di, &hp, &env, types::DefaultLogger::new()));
+
+    let file = std::env::temp_dir().join("rsrelex_patterns_test.json");
+    let file = file.to_str().unwrap();
+    relex::write_patterns(&result.patterns, file);
+    let saved = relex::read_patterns(file);
+    std::fs::remove_file(file).unwrap();
+    assert_eq!(saved, result.patterns);
+
+    // different corpus, different word numbers
+    let mut news = Env::new();
+    xml::add_text_to_env("Officials said Vienna is the capital of Austria. \
+        Bern is the capital of Switzerland.", &mut news, &segmentation::Tokenizer::Unicode);
+
+    let applied = rt.block_on(relex::apply_patterns(&saved, &types::RelexOptions::default(),
+        &hp, &news, types::DefaultLogger::new()));
+
+    let pairs: Vec<(&str, &str)> = applied.pairs.iter()
+        .map(|p| (p.w1.as_str(), p.w2.as_str()))
+        .collect();
+    assert_eq!(pairs.len(), 2);
+    assert!(pairs.contains(&("Austria", "Vienna")));
+    assert!(pairs.contains(&("Switzerland", "Bern")));
+}
+
+#[test]
+fn test_infix_word_nr() {
+    let mut env = Env::new();
+    let capital = env.add_word("capital");
+
+    assert_eq!(env.get_infix_word_nr("capital"), Some(capital));
+    assert_eq!(env.get_infix_word_nr("*"), Some(types::WILDCARD));
+    assert_eq!(env.get_infix_word_nr("*{0,3}"), Some(types::gap(3)));
+    assert_eq!(env.get_infix_word_nr("*{0,253}"), Some(types::gap(253)));
+    // too long to be encoded, no panic
+    assert_eq!(env.get_infix_word_nr("*{0,254}"), Option::None);
+    assert_eq!(env.get_infix_word_nr("*{0,300}"), Option::None);
+    assert_eq!(env.get_infix_word_nr("<NOUN>"), Option::None);
+    assert_eq!(env.get_infix_word_nr("city"), Option::None);
+
+    let nr = env.get_infix_word_nr("*{0,3}").unwrap();
+    assert_eq!(env.get_infix_word(&nr), "*{0,3}");
+}
+
+#[test]
+fn test_relex_options_default() {
+    let di: types::DipreInput =
+        serde_json::from_str(r#"{"pairs": [["Germany", "Berlin"]]}"#).unwrap();
+
+    assert_eq!(di.options.iterations, 1);
+    assert!(di.options.pos_generalise.is_empty());
+
+    let di: types::DipreInput = serde_json::from_str(
+        r#"{"pairs": [], "options": {"iterations": 3}}"#).unwrap();
+
+    assert_eq!(di.options.iterations, 3);
+    assert_eq!(di.options.max_new_pairs_per_round, 10);
+}
+
+#[test]
+fn test_relex_hyper_parameter_vector() {
+    let v = relex::DEFAULT_RELEX_HYPER_PARAMETER.to_vector();
+    let hp = relex::RelexHyperParameter::from_vector(v.clone(), 5.0);
+
+    assert_eq!(hp.to_vector(), v);
+    assert_eq!(hp.pattern_survivor_threshold, 5.0);
+    assert_eq!(hp.wpair_survivor_threshold, 5.0);
+}
+
 
 // type Closure = dyn Fn(&Vec<usize>) -> Vec<usize>;
 
@@ -49,3 +371,456 @@ fn test_conex_config() {
 
 //     println!("{:?}", do_smthg_w_closure_holder(&closure_holder));
 // }
+
+#[test]
+fn test_relex_coupled_relations() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin is the capital of Germany. \
+        Paris is the capital of France. \
+        Rome is the capital of Italy. \
+        Hamburg is a city in Germany. \
+        Lyon is a city in France. \
+        Milan is a city in Italy. \
+        Berlin lies in Germany. \
+        Hamburg lies in Germany. \
+        Lyon lies in France.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let hp = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 5.0,
+        ..Default::default()
+    };
+
+    let relation = |name: &str, pairs: Vec<(&str, &str)>| types::Relation {
+        name: name.to_owned(),
+        pairs: pairs.iter().map(|(w1, w2)| (w1.to_string(), w2.to_string())).collect(),
+        negative_pairs: Vec::new(),
+        symmetric: None,
+        w1_type: None,
+        w2_type: None
+    };
+
+    let ci = types::CoupledDipreInput {
+        relations: vec!(
+            relation("capital", vec!(("Germany", "Berlin"), ("France", "Paris"))),
+            relation("located_in", vec!(("Germany", "Hamburg"), ("France", "Lyon")))),
+        options: types::RelexOptions::default()
+    };
+
+    let results = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex_coupled(ci, &hp, &env, types::DefaultLogger::new()));
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].name, "capital");
+    assert_eq!(results[1].name, "located_in");
+
+    let pairs = |r: &types::RelexResult| r.pairs.iter()
+        .map(|p| (p.w1.clone(), p.w2.clone()))
+        .collect::<Vec<_>>();
+    let capital = pairs(&results[0].result);
+    let located_in = pairs(&results[1].result);
+
+    assert!(capital.contains(&("Italy".to_owned(), "Rome".to_owned())));
+    assert!(located_in.contains(&("Italy".to_owned(), "Milan".to_owned())));
+
+    // no wpair and no infix is shared between the relations
+    assert!(capital.iter().all(|p| !located_in.contains(p)));
+    assert!(results[0].result.patterns.iter()
+        .all(|p| results[1].result.patterns.iter().all(|q| p.infix != q.infix)));
+}
+
+#[test]
+fn test_relex_symmetric_relation() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Germany shares a border with France. \
+        France shares a border with Germany. \
+        Spain shares a border with France. \
+        Austria shares a border with Italy.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let hp = relex::RelexHyperParameter {
+        pattern_survivor_threshold: 5.0,
+        wpair_survivor_threshold: 5.0,
+        ..Default::default()
+    };
+
+    let run = |symmetric: bool| {
+        let mut di = types::DipreInput::new(vec!(("Germany", "France"), ("France", "Germany")));
+        di.options.symmetric = symmetric;
+        tokio::runtime::Runtime::new().unwrap()
+            .block_on(relex::do_relex(di, &hp, &env, types::DefaultLogger::new()))
+    };
+    let pairs = |result: &types::RelexResult| result.pairs.iter()
+        .map(|p| (p.w1.clone(), p.w2.clone()))
+        .collect::<Vec<_>>();
+
+    // directed: both directions are distinct patterns and pairs
+    let directed = run(false);
+    assert!(!directed.symmetric);
+    assert_eq!(directed.patterns.len(), 2);
+    assert!(directed.patterns.iter().all(|p| p.infix == vec!("shares", "a", "border", "with")));
+    assert_ne!(directed.patterns[0].order, directed.patterns[1].order);
+
+    let directed_pairs = pairs(&directed);
+    assert!(directed_pairs.contains(&("Germany".to_owned(), "France".to_owned())));
+    assert!(directed_pairs.contains(&("France".to_owned(), "Germany".to_owned())));
+
+    // symmetric: one pattern, every pair once in canonical order
+    let symmetric = run(true);
+    assert!(symmetric.symmetric);
+    assert_eq!(symmetric.patterns.len(), 1);
+
+    let symmetric_pairs = pairs(&symmetric);
+    assert_eq!(symmetric_pairs.len(), 3);
+    assert_eq!(symmetric_pairs.iter()
+        .filter(|(w1, w2)| (w1 == "Germany" && w2 == "France")
+            || (w1 == "France" && w2 == "Germany"))
+        .count(), 1);
+    assert!(symmetric_pairs.iter().any(|(w1, w2)| 
+        (w1 == "Austria" && w2 == "Italy") || (w1 == "Italy" && w2 == "Austria")));
+}
+
+#[test]
+fn test_relex_argument_spans() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Washington is the capital of the United States. \
+        Berlin is the capital of Germany. \
+        Paris is the capital of France. \
+        Wellington is the capital of New Zealand.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let hp = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 5.0,
+        ..Default::default()
+    };
+
+    let run = |seeds: Vec<(&str, &str)>, arguments: types::RelexArguments| {
+        let mut di = types::DipreInput::new(seeds);
+        di.options.arguments = arguments;
+        tokio::runtime::Runtime::new().unwrap()
+            .block_on(relex::do_relex(di, &hp, &env, types::DefaultLogger::new()))
+    };
+    let pairs = |result: &types::RelexResult| result.pairs.iter()
+        .map(|p| (p.w1.clone(), p.w2.clone()))
+        .collect::<Vec<_>>();
+
+    let spans = run(vec!(("United States", "Washington"), ("Germany", "Berlin")),
+        types::RelexArguments::Capitalised);
+
+    // "the" next to the argument is skipped, not part of the infix
+    assert_eq!(spans.patterns.len(), 1);
+    assert_eq!(spans.patterns[0].infix, vec!("is", "the", "capital", "of"));
+
+    let span_pairs = pairs(&spans);
+    assert!(span_pairs.contains(&("United States".to_owned(), "Washington".to_owned())));
+    assert!(span_pairs.contains(&("New Zealand".to_owned(), "Wellington".to_owned())));
+    assert!(span_pairs.contains(&("France".to_owned(), "Paris".to_owned())));
+
+    // single words only find the first word of an argument
+    let tokens = pairs(&run(vec!(("Germany", "Berlin"), ("France", "Paris")),
+        types::RelexArguments::Token));
+    assert!(tokens.contains(&("New".to_owned(), "Wellington".to_owned())));
+}
+
+#[test]
+fn test_relex_argument_types() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin is the capital of Germany. \
+        Paris is the capital of France. \
+        Rome is the capital of Italy. \
+        Fashion is the capital of style.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let hp = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 5.0,
+        ..Default::default()
+    };
+
+    let run = |options: types::RelexOptions| {
+        let mut di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+        di.options = options;
+        tokio::runtime::Runtime::new().unwrap()
+            .block_on(relex::do_relex(di, &hp, &env, types::DefaultLogger::new()))
+            .pairs.into_iter()
+            .map(|p| (p.w1, p.w2, p.fitness))
+            .collect::<Vec<_>>()
+    };
+    let countries = types::ArgumentType {
+        words: vec!("Germany".to_owned(), "France".to_owned(), "Italy".to_owned()),
+        ..Default::default()
+    };
+    let has_style = |pairs: &[(String, String, f64)]| pairs.iter()
+        .any(|(w1, _, _)| w1 == "style");
+
+    let untyped = run(types::RelexOptions::default());
+    assert!(has_style(&untyped));
+
+    // the penalty only lowers the fitness of pairs outside the type
+    let penalised = run(types::RelexOptions {
+        w1_type: Some(countries.clone()),
+        argument_type_penalty: 1.0,
+        ..Default::default()
+    });
+    let fitness = |pairs: &[(String, String, f64)], w1: &str| pairs.iter()
+        .find(|p| p.0 == w1).map(|p| p.2).unwrap();
+    assert_approx_eq!(fitness(&untyped, "style") - 1.0, fitness(&penalised, "style"));
+    assert_approx_eq!(fitness(&untyped, "Italy"), fitness(&penalised, "Italy"));
+
+    let dropped = run(types::RelexOptions {
+        w1_type: Some(countries),
+        drop_untyped_pairs: true,
+        ..Default::default()
+    });
+    assert!(!has_style(&dropped));
+    assert_eq!(dropped.len(), 3);
+}
+
+#[test]
+fn test_relex_discover_relations() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin is the capital of Germany. \
+        Paris is the capital of France. \
+        Rome is the capital of Italy. \
+        Berlin is the center of Germany. \
+        Paris is the center of France. \
+        Germany shares a border with France. \
+        Italy shares a border with France. \
+        Spain shares a border with France. \
+        Germany shares a border with Austria. \
+        Austria shares a border with Italy.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let clusters = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::discover_relations(&types::DiscoveryOptions::default(),
+            &types::RelexOptions::default(),
+            &relex::RelexHyperParameter::default(),
+            &env,
+            types::DefaultLogger::new()));
+
+    let infixes = |c: &types::RelationCluster| c.patterns.iter()
+        .map(|p| p.infix.join(" "))
+        .collect::<Vec<_>>();
+    let pairs = |c: &types::RelationCluster| c.pairs.iter()
+        .map(|p| (p.w1.clone(), p.w2.clone()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(infixes(&clusters[0]), vec!("shares a border with"));
+    assert_eq!(clusters[0].pairs.len(), 4);
+    assert!(!pairs(&clusters[0]).contains(&("Spain".to_owned(), "France".to_owned())));
+
+    assert_eq!(infixes(&clusters[1]), vec!("is the capital of", "is the center of"));
+    assert_eq!(pairs(&clusters[1])[..2],
+        [("Berlin".to_owned(), "Germany".to_owned()), ("Paris".to_owned(), "France".to_owned())]);
+    assert_eq!(clusters[1].pairs[0].patterns, vec!(0, 1));
+    // Rome occurs in a single sentence only
+    assert_eq!(pairs(&clusters[1]).len(), 2);
+}
+
+#[test]
+fn test_relex_explain_pair() {
+    let env = capital_env();
+    let di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+
+    let hp = relex::RelexHyperParameter {
+        wpair_survivor_threshold: 5.0,
+        ..Default::default()
+    };
+
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &hp, &env, types::DefaultLogger::new()));
+
+    let explanation = relex::explain_pair(&result, "Italy", "Rome",
+        &types::RelexOptions::default(), &env, 5).unwrap();
+
+    assert_eq!(explanation.patterns.len(), 1);
+    assert_eq!(explanation.patterns[0].pattern.infix, vec!("is", "the", "capital", "of"));
+    assert_eq!(explanation.patterns[0].sentence_count, 1);
+    assert_eq!(explanation.patterns[0].sentences, vec!("Rome is the capital of Italy."));
+
+    // directed pairs are only explained in their order
+    assert!(relex::explain_pair(&result, "Rome", "Italy",
+        &types::RelexOptions::default(), &env, 5).is_none());
+}
+
+#[test]
+fn test_conex_explain_term() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin has a mayor. \
+        Paris has a mayor. \
+        Rome has a cathedral. \
+        London elects a mayor.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    // coocs shared by both seeds survive, as do their coocs with two of them
+    let hp = conex::ConexHyperParameter::from_vector(
+        vec!(50.0, 0.0, 0.0, 50.0, 0.0, 0.0), 100.0);
+    let cooc_input = types::CoocInput::new(vec!("Berlin", "Paris"));
+
+    let explanation = conex::explain_term(&cooc_input, "London", &hp, &env, 5).unwrap();
+
+    assert_approx_eq!(explanation.fitness, 150.0);
+    assert_eq!(explanation.seeds, vec!("Berlin", "Paris"));
+
+    let words: Vec<&str> = explanation.coocs.iter().map(|c| c.word.as_str()).collect();
+    assert_eq!(words.len(), 3);
+    assert!(words.contains(&"mayor"));
+    assert!(!words.contains(&"has"));
+
+    let mayor = explanation.coocs.iter().find(|c| c.word == "mayor").unwrap();
+    assert_eq!(mayor.seeds, vec!("Berlin", "Paris"));
+    assert_eq!(mayor.sentence_count, 1);
+    assert_eq!(mayor.sentences, vec!("London elects a mayor."));
+
+    assert!(conex::explain_term(&cooc_input, "Madrid", &hp, &env, 5).is_none());
+}
+
+#[test]
+fn test_relex_default_hyper_parameter() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin is the capital of Germany. \
+        Paris is the capital of France. \
+        Rome is the capital of Italy. \
+        Berlin is capital city of Germany. \
+        Paris is capital city of France. \
+        Rome is capital city of Italy. \
+        Madrid is the capital of Spain.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let di = types::DipreInput::new(vec!(("Germany", "Berlin"), ("France", "Paris")));
+
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &relex::RelexHyperParameter::default(), &env,
+            types::DefaultLogger::new()));
+
+    assert_eq!(result.patterns.len(), 2);
+
+    // found by both patterns, the small frequency malus of rare words
+    // does not push the pair below the threshold
+    let pairs: Vec<(&str, &str, f64)> = result.pairs.iter()
+        .map(|p| (p.w1.as_str(), p.w2.as_str(), p.fitness))
+        .collect();
+    assert!(pairs.contains(&("Italy", "Rome", 20.0)));
+    // found by a single pattern only
+    assert!(!pairs.iter().any(|(w1, _, _)| *w1 == "Spain"));
+}
+
+#[test]
+fn test_relex_argument_words_apart() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Washington is the capital of the United States. \
+        Paris is the capital of France. \
+        Washington praised the United Kingdom and the States.",
+        &mut env, &segmentation::Tokenizer::Unicode);
+
+    let mut di = types::DipreInput::new(vec!(("United States", "Washington"), ("France", "Paris")));
+    di.options.arguments = types::RelexArguments::Capitalised;
+
+    // the last sentence contains every word of the first seed, but
+    // not "United States" as a whole
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &relex::RelexHyperParameter::default(), &env,
+            types::DefaultLogger::new()));
+
+    assert_eq!(result.patterns.len(), 1);
+    assert_eq!(result.patterns[0].infix, vec!("is", "the", "capital", "of"));
+}
+
+// collects the log lines of a relex run
+struct CollectLogger {
+    lines: std::sync::Arc<std::sync::Mutex<Vec<String>>>
+}
+
+#[async_trait::async_trait]
+impl types::AsyncLogger for CollectLogger {
+    async fn log(&mut self, s: String) {
+        self.lines.lock().unwrap().push(s);
+    }
+}
+
+fn relex_with_log(di: types::DipreInput, env: &Env) -> (types::RelexResult, Vec<String>) {
+    let lines = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let result = tokio::runtime::Runtime::new().unwrap()
+        .block_on(relex::do_relex(di, &relex::RelexHyperParameter::default(), env,
+            CollectLogger { lines: lines.clone() }));
+    let lines = lines.lock().unwrap().clone();
+    (result, lines)
+}
+
+#[test]
+fn test_relex_bootstrap_rounds() {
+    let mut env = Env::new();
+    xml::add_text_to_env("Berlin is the capital of Germany. \
+        Paris is the capital of France. \
+        Rome is the capital of Italy. \
+        Madrid is the capital of Spain. \
+        Berlin is capital city of Germany. \
+        Paris is capital city of France. \
+        Rome is capital city of Italy. \
+        Madrid is capital city of Spain. \
+        Berlin is the seat of Germany. \
+        Rome is the seat of Italy. \
+        Berlin is the centre of Germany. \
+        Rome is the centre of Italy. \
+        Vienna is the capital of Austria. \
+        Vienna is the seat of Austria. \
+        Bern is the seat of Switzerland. \
+        Bern is the centre of Switzerland. \
+        Berlin lies deep in Germany. \
+        Vienna lies deep in Austria. \
+        Warsaw lies deep in Poland. \
+        Warsaw is the capital of Poland. \
+        Berlin is the heart of Germany. \
+        Bern is the heart of Switzerland. \
+        Budapest is the heart of Hungary. \
+        Budapest is the capital of Hungary.", &mut env, &segmentation::Tokenizer::Unicode);
+
+    let seeds = vec!(("Germany", "Berlin"), ("France", "Paris"));
+    let found = |result: &types::RelexResult, w1: &str| result.pairs.iter()
+        .any(|p| p.w1 == w1);
+    let promoting = |log: &[String]| log.iter()
+        .filter(|l| l.contains(": promoting "))
+        .cloned()
+        .collect::<Vec<String>>();
+
+    // Italy and Spain are promoted in round 1. Italy teaches "is the seat
+    // of" and "is the centre of" in round 2, which find Austria and
+    // Switzerland. Austria, also found by the seed pattern "is the
+    // capital of", is promoted in round 2 and teaches "lies deep in" in
+    // round 3, which finds Poland. Switzerland is only found by patterns
+    // not learned from the original seeds, it is not promoted, so
+    // "is the heart of" is never learned and Hungary never found.
+    let mut di = types::DipreInput::new(seeds.clone());
+    di.options.iterations = 10;
+    let (result, log) = relex_with_log(di, &env);
+
+    assert_eq!(promoting(&log), vec!(
+        "round 1: promoting 2 new seed wpairs.",
+        "round 2: promoting 1 new seed wpairs.",
+        "round 3: promoting 1 new seed wpairs.",
+        "round 4: promoting 0 new seed wpairs."));
+    // stops before the last round without new seeds
+    assert!(log.contains(&"no new seed wpairs, stopping.".to_owned()));
+    assert!(!log.iter().any(|l| l.starts_with("starting round 5 ")));
+
+    assert!(found(&result, "Austria"));
+    assert!(found(&result, "Poland"));
+    assert!(found(&result, "Switzerland"));
+    assert!(!found(&result, "Hungary"));
+
+    // without the drift filter Switzerland is promoted as well
+    let mut di = types::DipreInput::new(seeds.clone());
+    di.options.iterations = 10;
+    di.options.min_seed_pattern_support = 0;
+    let (result, log) = relex_with_log(di, &env);
+
+    assert_eq!(promoting(&log)[1], "round 2: promoting 2 new seed wpairs.");
+    assert!(found(&result, "Hungary"));
+
+    // one new seed per round
+    let mut di = types::DipreInput::new(seeds.clone());
+    di.options.iterations = 2;
+    di.options.max_new_pairs_per_round = 1;
+    let (_, log) = relex_with_log(di, &env);
+
+    assert_eq!(promoting(&log), vec!("round 1: promoting 1 new seed wpairs."));
+
+    // a single round promotes nothing
+    let (result, log) = relex_with_log(types::DipreInput::new(seeds), &env);
+
+    assert!(promoting(&log).is_empty());
+    assert!(!found(&result, "Austria"));
+}
@@ -10,13 +10,62 @@ use bincode::{serialize_into, deserialize_from};
 use std::io::{BufWriter, BufReader};
 
 use async_trait::async_trait;
+use super::segmentation::{Tokenizer, is_non_space_script, is_non_space_token};
+use super::conex::ConexHyperParameter;
 
 pub type SentenceId = u32;
 pub type WordNr = u32;
+// a pair of arguments, each of one or more words
+pub type ArgumentPair = (Vec<WordNr>, Vec<WordNr>);
 
 // consider Option instead of an artificial 'null'
 pub const EMPTY_WORD: u32 = std::u32::MAX;
 
+// pattern infix slots matching every word with a certain part-of-speech
+// tag, encoded far above the dictionary range (like EMPTY_WORD)
+pub const POS_SLOT_BASE: u32 = 0xFFFF_0000;
+const POS_SLOT_END: u32 = 0xFFFF_FF00;
+
+pub fn pos_slot(tag: WordNr) -> WordNr {
+    POS_SLOT_BASE + tag
+}
+
+// returns the pos tag if w is a pos slot
+pub fn get_pos_slot(w: WordNr) -> Option<WordNr> {
+    if (POS_SLOT_BASE..POS_SLOT_END).contains(&w) {
+        Some(w - POS_SLOT_BASE)
+    } else {
+        Option::None
+    }
+}
+
+// pattern infix slot matching any single word
+pub const WILDCARD: u32 = 0xFFFF_FF00;
+
+// pattern infix slots matching a gap of 0 up to n words
+const GAP_BASE: u32 = 0xFFFF_FF01;
+const GAP_END: u32 = 0xFFFF_FFFF;
+
+pub fn gap(max_len: usize) -> WordNr {
+    assert!(max_len < (GAP_END - GAP_BASE) as usize, "gap of {} words is too long.", max_len);
+    GAP_BASE + max_len as u32
+}
+
+// returns the maximum gap length if w is a gap
+pub fn get_gap(w: WordNr) -> Option<usize> {
+    if (GAP_BASE..GAP_END).contains(&w) {
+        Some((w - GAP_BASE) as usize)
+    } else {
+        Option::None
+    }
+}
+
+// true for words of the dictionary, false for pos slots,
+// wildcards, gaps and EMPTY_WORD
+pub fn is_concrete_word(w: WordNr) -> bool {
+    w < POS_SLOT_BASE
+}
+
 pub fn soundness_test(env: &Env){
     // check if every dictionary word is associated with 
     // an inverted index entry
@@ -83,46 +132,51 @@ pub fn deserialize<T: DeserializeOwned>(bin_file: &str) -> T {
     o 
 }
 
+// w1 and w2 are arguments of one or more words
 #[derive(Debug)]
 pub struct WPair {
-    pub w1: WordNr,
-    pub w2: WordNr,
-    pub fitness: i16
+    pub w1: Vec<WordNr>,
+    pub w2: Vec<WordNr>,
+    pub fitness: f64
 }
 
 impl Clone for WPair {
    fn clone(&self) -> WPair {
         WPair {
-            w1: self.w1,
-            w2: self.w2,
+            w1: self.w1.clone(),
+            w2: self.w2.clone(),
             fitness: self.fitness
         }
     }
 }
 
 impl WPair {
-    pub fn new(w1: WordNr, w2: WordNr) -> WPair {
+    pub fn new(w1: Vec<WordNr>, w2: Vec<WordNr>) -> WPair {
         WPair {
            w1, w2,
-           fitness: 0i16 
+           fitness: 0f64 
         }
     }
 
     pub fn new_str(w1: &str, w2: &str, env: &Env) -> WPair {
 
-        let w1 = env.dict.get_opt_nr(w1).expect("w1 not found in dict.");
-        let w2 = env.dict.get_opt_nr(w2).expect("w2 not found in dict.");
+        let w1 = env.get_argument_nrs(w1).expect("w1 not found in dict.");
+        let w2 = env.get_argument_nrs(w2).expect("w2 not found in dict.");
 
         WPair::new(w1, w2)
     }
 
+    pub fn key(&self) -> (Vec<WordNr>, Vec<WordNr>) {
+        (self.w1.clone(), self.w2.clone())
+    }
+
     pub fn println(&self, env: &Env) {
         info!("fitness: {}, w1: {}, w2: {}",
                  self.fitness,
-                 if self.w1 == EMPTY_WORD { "empty" }
-                 else { &env.dict.get_word(&self.w1) },
-                 if self.w2 == EMPTY_WORD { "empty" }
-                 else { &env.dict.get_word(&self.w2) });
+                 if self.w1.is_empty() { "empty".to_owned() }
+                 else { env.get_argument(&self.w1) },
+                 if self.w2.is_empty() { "empty".to_owned() }
+                 else { env.get_argument(&self.w2) });
     }
 }
 
@@ -131,7 +185,10 @@ pub struct Pattern {
     pub infix: Vec<WordNr>,
     pub suffix: WordNr,
     pub order: bool,
-    pub fitness: i16
+    pub fitness: f64,
+    // title prefix of the documents the pattern was found in,
+    // empty if they differ (see RelexOptions.document_scope)
+    pub scope: String
 }
 
 impl Clone for Pattern {
@@ -141,7 +198,8 @@ impl Clone for Pattern {
             infix: self.infix.clone(),
             suffix: self.suffix,
             order: self.order,
-            fitness: self.fitness
+            fitness: self.fitness,
+            scope: self.scope.clone()
         }
     }
 }
@@ -151,7 +209,8 @@ impl Pattern {
                suffix: WordNr, order: bool) -> Pattern {
         Pattern {
             prefix, infix, suffix, order,
-            fitness: 0i16
+            fitness: 0f64,
+            scope: String::new()
         }
     }
 
@@ -164,9 +223,8 @@ impl Pattern {
 
                  {
                      self.infix.iter()
-                         .map(|word_nr|
-                              env.dict.get_word(&word_nr))
-                         .collect::<Vec<&str>>()
+                         .map(|word_nr| env.get_infix_word(word_nr))
+                         .collect::<Vec<String>>()
                  },
 
                  if self.suffix == EMPTY_WORD { "empty" }
@@ -253,6 +311,19 @@ impl Dict {
     pub fn get_word <'a> (&'a self, n: &WordNr ) -> &'a str{
         & self.dict_vec[*n as usize]
     }
+
+    pub fn add_word(&mut self, w: &str) -> WordNr {
+        if self.dict.contains_key(w) {
+            self.dict[w]
+        } else {
+            let i = self.dict_vec.len() as WordNr; 
+
+            //TODO rly two copies needed?
+            self.dict_vec.push(w.to_owned());
+            self.dict.insert(w.to_owned(), i);
+            i
+        }
+    }
     
     pub fn serialize(&self, dir: String) {
         serialize_with_directory(self, dir, Dict::FILE_NAME);
@@ -263,11 +334,116 @@ impl Dict {
     }
 }
 
+// word level annotations (lemma, part-of-speech), parallel to Sentences.
+// Corpora imported without annotations have empty layers.
+#[derive(Serialize, Deserialize, Default)]
+pub struct AnnotationLayer {
+    pub dict: Dict,
+    pub sentences: Vec<Vec<WordNr>>
+}
+
+impl AnnotationLayer {
+    pub const LEMMA_FILE_NAME: &'static str = "lemma.bin";
+    pub const POS_FILE_NAME: &'static str = "pos.bin";
+
+    pub fn new() -> AnnotationLayer {
+        AnnotationLayer {
+            dict: Dict::new(),
+            sentences: Vec::new()
+        }
+    }
+
+    pub fn add_sentence(&mut self, annotations: &[&str]) {
+        let sent = annotations.iter()
+            .map(|a| self.dict.add_word(a))
+            .collect();
+        self.sentences.push(sent);
+    }
+
+    // annotation of the word at position i of sentence s_id
+    pub fn get(&self, s_id: &SentenceId, i: usize) -> Option<WordNr> {
+        self.sentences.get(*s_id as usize)
+            .and_then(|sent| sent.get(i))
+            .copied()
+    }
+
+    pub fn serialize(&self, dir: String, file_name: &str) {
+        if !self.sentences.is_empty() {
+            serialize_with_directory(self, dir, file_name);
+        }
+    }
+
+    pub fn deserialize(dir: String, file_name: &str) -> AnnotationLayer {
+        if std::path::Path::new(
+            &build_directory_string(dir.clone(), file_name)).exists() {
+            deserialize_with_directory(dir, file_name)
+        } else {
+            AnnotationLayer::new()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Document {
+    // e.g. file name, PMID
+    pub id: String,
+    pub title: String,
+    pub first_sentence: SentenceId,
+    pub sentence_count: u32
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Documents {
+    // ordered by first_sentence
+    pub documents: Vec<Document>
+}
+
+impl Documents {
+    pub const FILE_NAME: &'static str = "docs.bin";
+
+    pub fn new() -> Documents {
+        Documents {
+            documents: Vec::new()
+        }
+    }
+
+    pub fn get_document(&self, s_id: &SentenceId) -> Option<&Document> {
+        let i = match self.documents
+            .binary_search_by_key(s_id, |d| d.first_sentence) {
+            Ok(i) => i,
+            Err(0) => return Option::None,
+            Err(i) => i - 1
+        };
+
+        let d = &self.documents[i];
+        if *s_id < d.first_sentence + d.sentence_count { Some(d) }
+        else { Option::None }
+    }
+
+    pub fn serialize(&self, dir: String) {
+        serialize_with_directory(self, dir, Documents::FILE_NAME);
+    }
+
+    // bin-file directories written before documents were
+    // recorded do not contain a documents file
+    pub fn deserialize(dir: String) -> Documents {
+        if std::path::Path::new(
+            &build_directory_string(dir.clone(), Documents::FILE_NAME)).exists() {
+            deserialize_with_directory(dir, Documents::FILE_NAME)
+        } else {
+            Documents::new()
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct Env {
     pub sentences: Sentences,
     pub inverted_idx: InvertedIndex,
     pub dict: Dict,
+    pub documents: Documents,
+    pub lemmas: AnnotationLayer,
+    pub pos: AnnotationLayer,
     pub _pairs: Vec<WPair>,
     pub the: WordNr
 }
@@ -278,6 +454,9 @@ impl Env {
             sentences: Sentences::new(),
             inverted_idx: InvertedIndex::new(),
             dict: Dict::new(),
+            documents: Documents::new(),
+            lemmas: AnnotationLayer::new(),
+            pos: AnnotationLayer::new(),
             _pairs: Vec::new(), 
             the: EMPTY_WORD
         }
@@ -293,17 +472,123 @@ impl Env {
         &self.sentences.sentences[*s_id as usize]
     }
 
+    // resolves pattern infix words, pos slots are shown as <TAG>
+    // inverse of get_infix_word
+    pub fn get_infix_word_nr(&self, w: &str) -> Option<WordNr> {
+        if w == "*" {
+            return Some(WILDCARD);
+        }
+        if let Some(max_len) = w.strip_prefix("*{0,").and_then(|n| n.strip_suffix('}')) {
+            // a gap too long to be encoded is unknown like an unknown word
+            return max_len.parse().ok()
+                .filter(|n| *n < (GAP_END - GAP_BASE) as usize)
+                .map(gap);
+        }
+        if let Some(tag) = w.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+            if let Some(tag) = self.pos.dict.get_opt_nr(tag) {
+                return Some(pos_slot(tag));
+            }
+        }
+        self.dict.get_opt_nr(w)
+    }
+
+    pub fn get_infix_word(&self, w: &WordNr) -> String {
+        match get_pos_slot(*w) {
+            Some(tag) => format!("<{}>", self.pos.dict.get_word(&tag)),
+            None if *w == WILDCARD => "*".to_owned(),
+            None if get_gap(*w).is_some() => format!("*{{0,{}}}", get_gap(*w).unwrap()),
+            None => self.dict.get_word(w).to_owned()
+        }
+    }
+
+    // the words of an argument, None for unknown words. A dictionary
+    // word is taken as is, otherwise the argument is split at unicode
+    // word boundaries. Runs of non-space script characters, which these
+    // split into single characters, are matched to the longest
+    // dictionary words (like a corpus segmented by maxmatch).
+    pub fn get_argument_nrs(&self, argument: &str) -> Option<Vec<WordNr>> {
+        if let Some(w) = self.dict.get_opt_nr(argument) {
+            return Some(vec!(w));
+        }
+
+        let tokens = Tokenizer::Unicode.tokenize(argument);
+        let mut words = Vec::new();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let run_end = (i..tokens.len())
+                .find(|j| !is_non_space_token(tokens[*j]))
+                .unwrap_or(tokens.len())
+                .max(i + 1);
+
+            let (w, end) = (i + 1..=run_end).rev()
+                .find_map(|j| self.dict.get_opt_nr(&tokens[i..j].concat()).map(|w| (w, j)))?;
+
+            words.push(w);
+            i = end;
+        }
+
+        Some(words).filter(|words| !words.is_empty())
+    }
+
+    // an argument of several words as text, no space before punctuation
+    // and between words of non-space scripts
+    pub fn get_argument(&self, words: &[WordNr]) -> String {
+        let mut argument = String::new();
+        for w in words {
+            let word = self.dict.get_word(w);
+            let non_space = argument.ends_with(is_non_space_script)
+                && word.starts_with(is_non_space_script);
+            if !argument.is_empty() && word.starts_with(char::is_alphanumeric) && !non_space {
+                argument.push(' ');
+            }
+            argument.push_str(word);
+        }
+        argument
+    }
+
+    pub fn get_document(&self, s_id: &SentenceId) -> Option<&Document> {
+        self.documents.get_document(s_id)
+    }
+
     pub fn add_word(&mut self, w: &str) -> WordNr {
-        if self.dict.dict.contains_key(w) {
-            self.dict.dict[w]
-        } else {
-            let i = self.dict.dict_vec.len() as WordNr; 
+        self.dict.add_word(w)
+    }
 
-            //TODO rly two copies needed?
-            self.dict.dict_vec.push(w.to_owned());
-            self.dict.dict.insert(w.to_owned(), i);
-            i
+    // adds an already tokenized sentence
+    pub fn add_sentence(&mut self, words: &[&str]) -> SentenceId {
+        let s_id = self.sentences.sentences.len() as SentenceId;
+        let sent: Vec<WordNr> = words.iter()
+            .map(|w| self.add_word(w))
+            .collect();
+
+        for w in &sent {
+            self.add_inv_idx(*w, s_id);
+        }
+
+        self.sentences.sentences.push(sent);
+        s_id
+    }
+
+    pub fn has_pos(&self) -> bool {
+        !self.pos.sentences.is_empty()
+    }
+
+    // counts the part-of-speech tags over every occurrence of w
+    pub fn get_pos_counts(&self, w: &WordNr) -> HashMap<WordNr, usize> {
+        let mut tag_on_count: HashMap<WordNr, usize> = HashMap::new();
+
+        for s_id in self.get_inverted_idx(w) {
+            for (i, w_) in self.get_sentence(s_id).iter().enumerate() {
+                if w_ == w {
+                    if let Some(tag) = self.pos.get(s_id, i) {
+                        *tag_on_count.entry(tag).or_insert(0) += 1;
+                    }
+                }
+            }
         }
+
+        tag_on_count
     }
 
     pub fn add_inv_idx(&mut self, w: WordNr, s_id: SentenceId) {
@@ -315,6 +600,9 @@ impl Env {
     pub fn serialize(&self, dir: String) {
         self.inverted_idx.serialize(dir.clone());
         self.sentences.serialize(dir.clone());
+        self.documents.serialize(dir.clone());
+        self.lemmas.serialize(dir.clone(), AnnotationLayer::LEMMA_FILE_NAME);
+        self.pos.serialize(dir.clone(), AnnotationLayer::POS_FILE_NAME);
         self.dict.serialize(dir);
     }
 
@@ -322,14 +610,154 @@ impl Env {
         let mut e = Env::new();
         e.dict = Dict::deserialize(dir.clone());
         e.sentences = Sentences::deserialize(dir.clone());
+        e.documents = Documents::deserialize(dir.clone());
+        e.lemmas = AnnotationLayer::deserialize(dir.clone(), AnnotationLayer::LEMMA_FILE_NAME);
+        e.pos = AnnotationLayer::deserialize(dir.clone(), AnnotationLayer::POS_FILE_NAME);
         e.inverted_idx = InvertedIndex::deserialize(dir);
         e
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RelexScoring {
+    // fitness is the sum of the boosts of RelexHyperParameter
+    #[default]
+    Boost,
+    // Snowball: the boosts only select candidate patterns, their fitness
+    // is the confidence on the seed pairs, the fitness of a pair is the
+    // noisy-or of the confidences of its patterns
+    Confidence
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RelexArguments {
+    // the single words next to the infix ("the" is skipped after it)
+    #[default]
+    Token,
+    // sequences of capitalised words, e.g. "United States"
+    Capitalised,
+    // sequences of words tagged with argument_pos, needs a conllu import
+    Pos
+}
+
+// the words allowed as an argument, given as a word list and / or
+// expanded by conex from seed terms
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ArgumentType {
+    pub words: Vec<String>,
+    // conex seed terms, they and the words conex finds are allowed
+    pub conex_seeds: Vec<String>,
+    // defaults to DEFAULT_CONEX_HYPER_PARAMETER
+    pub conex_hyper_parameter: Option<ConexHyperParameter>,
+    // keep only conex words mostly tagged with one of these tags
+    pub conex_pos_filter: Vec<String>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RelexOptions {
+    // part-of-speech tags (e.g. "NOUN", "ADJ") which are generalised
+    // to pos slots in pattern infixes, needs a conllu import
+    pub pos_generalise: Vec<String>,
+    // bootstrapping rounds, every round feeds the best
+    // new wpairs back as seeds
+    pub iterations: usize,
+    // maximum of wpairs promoted to seeds per round (0: unlimited)
+    pub max_new_pairs_per_round: usize,
+    // promoted wpairs need to be found by at least this many
+    // patterns learned from the original seeds (semantic drift)
+    pub min_seed_pattern_support: usize,
+    // infixes within this token edit distance are merged to a
+    // generalised pattern with wildcards and gaps (0: off)
+    pub max_infix_edit_distance: usize,
+    // maximum length of a gap in a generalised pattern
+    pub max_gap: usize,
+    // fitness malus of a pattern for every negative wpair it finds
+    pub negative_pattern_penalty: f64,
+    // drop patterns finding any negative wpair
+    pub drop_negative_patterns: bool,
+    pub scoring: RelexScoring,
+    // thresholds of the confidence scoring
+    pub min_pattern_confidence: f64,
+    pub min_pair_confidence: f64,
+    // maximum count of words between w1 and w2 (0: unlimited)
+    pub max_pair_distance: usize,
+    // patterns only match if the word before w1 / after w2 is the
+    // prefix / suffix they were learned with (unless it varied)
+    pub match_prefix: bool,
+    pub match_suffix: bool,
+    // patterns only match in documents whose title starts with the
+    // first document_scope characters of the titles they were
+    // learned from (unless they varied), 0: off
+    pub document_scope: usize,
+    // a symmetric relation (e.g. borders) doesn't distinguish (w1, w2)
+    // from (w2, w1): pairs are canonicalised and patterns are merged
+    // regardless of the order of w1 and w2. A directed relation keeps
+    // both directions as distinct pairs and patterns.
+    pub symmetric: bool,
+    // how the arguments next to the infix are detected
+    pub arguments: RelexArguments,
+    // maximum words of an argument
+    pub max_argument_len: usize,
+    // words and pos tags (e.g. determiners, adjectives) skipped
+    // between the infix and a capitalised / pos argument
+    pub argument_skip: Vec<String>,
+    pub argument_skip_pos: Vec<String>,
+    // pos tags of the words of a pos argument
+    pub argument_pos: Vec<String>,
+    // types of w1 and w2 (None: any word), arguments of several
+    // words are typed by the whole argument or their last word
+    pub w1_type: Option<ArgumentType>,
+    pub w2_type: Option<ArgumentType>,
+    // fitness malus of a wpair for every argument outside its type
+    pub argument_type_penalty: f64,
+    // drop wpairs with any argument outside its type
+    pub drop_untyped_pairs: bool
+}
+
+impl Default for RelexOptions {
+    fn default() -> RelexOptions {
+        RelexOptions {
+            pos_generalise: Vec::new(),
+            iterations: 1,
+            max_new_pairs_per_round: 10,
+            min_seed_pattern_support: 1,
+            max_infix_edit_distance: 0,
+            max_gap: 3,
+            negative_pattern_penalty: 10.0,
+            drop_negative_patterns: false,
+            scoring: RelexScoring::Boost,
+            min_pattern_confidence: 0.7,
+            min_pair_confidence: 0.7,
+            max_pair_distance: 0,
+            match_prefix: false,
+            match_suffix: false,
+            document_scope: 0,
+            symmetric: false,
+            arguments: RelexArguments::Token,
+            max_argument_len: 4,
+            argument_skip: vec!("the".to_owned(), "a".to_owned(), "an".to_owned()),
+            argument_skip_pos: vec!("DET".to_owned(), "ADJ".to_owned()),
+            argument_pos: vec!("PROPN".to_owned(), "NOUN".to_owned()),
+            w1_type: None,
+            w2_type: None,
+            argument_type_penalty: 10.0,
+            drop_untyped_pairs: false
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct DipreInput {
-    pub pairs: Vec<(String, String)>
+    pub pairs: Vec<(String, String)>,
+    // pairs which must not be extracted
+    #[serde(default)]
+    pub negative_pairs: Vec<(String, String)>,
+    #[serde(default)]
+    pub options: RelexOptions
 }
 
 impl DipreInput {
@@ -337,7 +765,9 @@ impl DipreInput {
         DipreInput {
             pairs: pairs.iter()
                 .map(|(a, b)| ((*a).to_string(), (*b).to_string()))
-                .collect()
+                .collect(),
+            negative_pairs: Vec::new(),
+            options: RelexOptions::default()
         }
     }
 
@@ -352,6 +782,272 @@ impl DipreInput {
     }
 }
 
+// pattern of a relex result, with words instead of word numbers
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoredPattern {
+    pub prefix: Option<String>,
+    pub infix: Vec<String>,
+    pub suffix: Option<String>,
+    pub order: bool,
+    pub fitness: f64,
+    #[serde(default)]
+    pub scope: String
+}
+
+impl ScoredPattern {
+    pub fn new(pattern: &Pattern, env: &Env) -> ScoredPattern {
+        let word = |w: &WordNr| if *w == EMPTY_WORD { Option::None }
+            else { Some(env.dict.get_word(w).to_owned()) };
+
+        ScoredPattern {
+            prefix: word(&pattern.prefix),
+            infix: pattern.infix.iter()
+                .map(|w| env.get_infix_word(w))
+                .collect(),
+            suffix: word(&pattern.suffix),
+            order: pattern.order,
+            fitness: pattern.fitness,
+            scope: pattern.scope.clone()
+        }
+    }
+
+    // maps the words back to word numbers of env, None if an infix
+    // word or pos tag is unknown. Unknown prefixes / suffixes are
+    // treated as varying (EMPTY_WORD).
+    pub fn to_pattern(&self, env: &Env) -> Option<Pattern> {
+        let word = |w: &Option<String>| w.as_ref()
+            .and_then(|w| env.dict.get_opt_nr(w))
+            .unwrap_or(EMPTY_WORD);
+
+        let infix = self.infix.iter()
+            .map(|w| env.get_infix_word_nr(w))
+            .collect::<Option<Vec<WordNr>>>()?;
+
+        let mut pattern = Pattern::new(word(&self.prefix), infix, 
+            word(&self.suffix), self.order);
+        pattern.fitness = self.fitness;
+        pattern.scope = self.scope.clone();
+        Some(pattern)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoredPair {
+    pub w1: String,
+    pub w2: String,
+    pub fitness: f64,
+    // indices into RelexResult.patterns of the patterns which found the pair
+    pub patterns: Vec<usize>
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct RelexResult {
+    // surviving patterns of the last round, sorted by fitness
+    pub patterns: Vec<ScoredPattern>,
+    // surviving pairs of the last round, sorted by fitness
+    pub pairs: Vec<ScoredPair>,
+    // the pairs are unordered (see RelexOptions.symmetric)
+    #[serde(default)]
+    pub symmetric: bool
+}
+
+impl RelexResult {
+    pub fn serialize(&self) -> String {
+        serde_json::to_string(self)
+            .expect("Could not serialize RelexResult to JSON String")
+    }
+}
+
+// seed tuples of an n-ary relation, every tuple has the same arity
+#[derive(Serialize, Deserialize)]
+pub struct TupleInput {
+    pub tuples: Vec<Vec<String>>,
+    #[serde(default)]
+    pub options: RelexOptions
+}
+
+// the infix segments between the arguments of a tuple,
+// order[i] is the argument in front of segment i (the last
+// argument follows the last segment)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoredTuplePattern {
+    pub segments: Vec<Vec<String>>,
+    pub order: Vec<usize>,
+    pub fitness: f64
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoredTuple {
+    pub arguments: Vec<String>,
+    pub fitness: f64,
+    // indices of the patterns in TupleResult.patterns
+    pub patterns: Vec<usize>
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct TupleResult {
+    // sorted by fitness
+    pub patterns: Vec<ScoredTuplePattern>,
+    pub tuples: Vec<ScoredTuple>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DiscoveryOptions {
+    // entities occurring in fewer sentences are ignored
+    pub min_entity_frequency: usize,
+    // patterns connecting fewer distinct entity pairs are ignored
+    pub min_pattern_pairs: usize,
+    // a pattern joins the cluster whose pairs overlap most with its
+    // pairs (jaccard), if the overlap is at least this
+    pub min_cluster_similarity: f64,
+    // example pairs per cluster
+    pub max_examples: usize
+}
+
+impl Default for DiscoveryOptions {
+    fn default() -> DiscoveryOptions {
+        DiscoveryOptions {
+            min_entity_frequency: 2,
+            min_pattern_pairs: 2,
+            min_cluster_similarity: 0.2,
+            max_examples: 10
+        }
+    }
+}
+
+// patterns connecting mostly the same entity pairs, a relation
+// found without seed pairs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RelationCluster {
+    // sum of the fitness of the patterns
+    pub fitness: f64,
+    // sorted by fitness
+    pub patterns: Vec<ScoredPattern>,
+    // example pairs, the fitness is the count of patterns connecting them
+    pub pairs: Vec<ScoredPair>
+}
+
+// why a relex pair was extracted
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PairExplanation {
+    pub w1: String,
+    pub w2: String,
+    pub fitness: f64,
+    // the patterns which found the pair
+    pub patterns: Vec<PatternEvidence>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PatternEvidence {
+    pub pattern: ScoredPattern,
+    // count of sentences the pattern found the pair in
+    pub sentence_count: usize,
+    // sample of these sentences
+    pub sentences: Vec<String>
+}
+
+// why a conex term was extracted
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TermExplanation {
+    pub term: String,
+    pub fitness: f64,
+    // seed words co-occurring with one of the coocs
+    pub seeds: Vec<String>,
+    // surviving first-order co-occurrences (CoocFst) of the seed words
+    // which co-occur with the term, sorted by fitness
+    pub coocs: Vec<CoocEvidence>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CoocEvidence {
+    pub word: String,
+    pub fitness: f64,
+    // seed words the word co-occurs with
+    pub seeds: Vec<String>,
+    // count of sentences containing the word and the term
+    pub sentence_count: usize,
+    // sample of these sentences
+    pub sentences: Vec<String>
+}
+
+// a (hypernym, hyponym) pair found by hearst templates
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HypernymPair {
+    pub hypernym: String,
+    pub hyponym: String,
+    // count of sentences per template the pair was found in
+    pub count: usize,
+    // indices into HearstResult.templates of the matching templates
+    pub templates: Vec<usize>
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct HearstResult {
+    pub templates: Vec<String>,
+    // sorted by count
+    pub pairs: Vec<HypernymPair>
+}
+
+// a gold pair, each word with its aliases
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GoldPair {
+    pub w1: Vec<String>,
+    pub w2: Vec<String>
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct PatternEvaluation {
+    pub infix: Vec<String>,
+    pub fitness: f64,
+    // pairs found by the pattern and how many of them are gold pairs
+    pub pairs: usize,
+    pub correct: usize,
+    pub precision: f64
+}
+
+// comparison of a RelexResult with gold pairs
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct RelexEvaluation {
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+    // (k, precision of the k pairs with the highest fitness)
+    pub precision_at_k: Vec<(usize, f64)>,
+    // in the order of RelexResult.patterns
+    pub patterns: Vec<PatternEvaluation>
+}
+
+// seed pairs of one of several relations extracted together
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Relation {
+    pub name: String,
+    pub pairs: Vec<(String, String)>,
+    #[serde(default)]
+    pub negative_pairs: Vec<(String, String)>,
+    // override RelexOptions.symmetric / w1_type / w2_type for this relation
+    #[serde(default)]
+    pub symmetric: Option<bool>,
+    #[serde(default)]
+    pub w1_type: Option<ArgumentType>,
+    #[serde(default)]
+    pub w2_type: Option<ArgumentType>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CoupledDipreInput {
+    pub relations: Vec<Relation>,
+    #[serde(default)]
+    pub options: RelexOptions
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RelationResult {
+    pub name: String,
+    #[serde(flatten)]
+    pub result: RelexResult
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CoocInput {
     pub set: Vec<String>
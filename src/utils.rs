@@ -1,5 +1,5 @@
 use super::*;
-use super::types::{Env};
+use super::types::{Env, GoldPair, ArgumentPair};
 use std::fs::File;
 use std::io::{self, BufRead, BufReader, Result};
 use log::debug;
@@ -49,3 +49,92 @@ pub fn read_words_from_stdin(env: &Env) -> Vec<WordNr> {
     word_nrs
 }
 
+// a pair per line, words separated by a tab (or whitespace)
+fn parse_pair(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    line.split_once('\t')
+        .or_else(|| line.split_once(char::is_whitespace))
+        .map(|(w1, w2)| (w1.trim(), w2.trim()))
+}
+
+pub fn read_pair_file(file_name: &str, env: &Env) -> Vec<ArgumentPair> {
+    let f = File::open(file_name)
+        .unwrap_or_else(|_| panic!("Unable to open pair file \"{}\".", file_name));
+
+    let lines: Vec<String> = BufReader::new(f).lines()
+        .collect::<Result<Vec<String>>>()
+        .unwrap_or_else(|_| panic!("Unable to read pair file \"{}\".", file_name));
+
+    let mut count_missing: usize = 0;
+
+    let pairs: Vec<ArgumentPair> = lines.iter()
+        .filter(|s| !s.trim().is_empty())
+        .filter_map(|s| {
+            let o_pair = parse_pair(s).and_then(|(w1, w2)| 
+                Some((env.get_argument_nrs(w1)?, env.get_argument_nrs(w2)?)));
+            if o_pair.is_none() {
+                debug!("Reference pair \"{}\" not found in dictionary", s);
+                count_missing += 1;
+            }
+            o_pair
+        }).collect();
+
+    let x: usize = pairs.len() + count_missing;
+    info!("{} from {} known pairs found in pair file \"{}\".", 
+        pairs.len(), x, file_name);
+
+    pairs
+}
+
+pub fn read_pairs_from_stdin(env: &Env) -> Vec<ArgumentPair> {
+
+    let lines: Vec<String> = std::io::stdin().lock().lines()
+        .collect::<Result<Vec<String>>>()
+        .unwrap_or_else(|_| panic!("Unable to read from stdin."));
+
+    lines.iter()
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| {
+            parse_pair(s).and_then(|(w1, w2)| 
+                Some((env.get_argument_nrs(w1)?, env.get_argument_nrs(w2)?)))
+                .unwrap_or_else(|| panic!("{} not found in dictionary. Aborting.", s))
+        }).collect()
+}
+
+// a gold pair per line like read_pair_file, aliases of a word are
+// separated by '|', e.g. "Germany|Federal Republic of Germany\tBerlin".
+// Words are kept as strings, gold pairs missing in the dict still count.
+pub fn read_gold_pair_file(file_name: &str) -> Vec<GoldPair> {
+    let f = File::open(file_name)
+        .unwrap_or_else(|_| panic!("Unable to open gold pair file \"{}\".", file_name));
+
+    let lines: Vec<String> = BufReader::new(f).lines()
+        .collect::<Result<Vec<String>>>()
+        .unwrap_or_else(|_| panic!("Unable to read gold pair file \"{}\".", file_name));
+
+    let aliases = |w: &str| w.split('|')
+        .map(|a| a.trim().to_owned())
+        .filter(|a| !a.is_empty())
+        .collect();
+
+    let gold: Vec<GoldPair> = lines.iter()
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| {
+            let (w1, w2) = parse_gold_pair(s)
+                .unwrap_or_else(|| panic!("Unable to parse gold pair \"{}\".", s));
+            GoldPair { w1: aliases(w1), w2: aliases(w2) }
+        }).collect();
+
+    info!("{} gold pairs read from \"{}\".", gold.len(), file_name);
+
+    gold
+}
+
+// aliases may contain spaces, so only a tab separates the words if present
+fn parse_gold_pair(line: &str) -> Option<(&str, &str)> {
+    if line.contains('\t') {
+        line.trim().split_once('\t')
+    } else {
+        parse_pair(line)
+    }
+}
@@ -1,4 +1,6 @@
-use super::types::{SentenceId, Env, soundness_test};
+use super::types::{SentenceId, Env, Document, soundness_test};
+use super::segmentation::Tokenizer;
+use super::checkpoint::{CheckpointConfig, ImportRun};
 
 use log::info;
 use std::fs::{read_dir};
@@ -14,7 +16,9 @@ pub fn read_xml_and_persist_env(
     output_dir: &str, 
     tag: &[u8], 
     limit: Option<usize>, 
-    preprocessor: Option<&PreprocessorFunction>) {
+    preprocessor: Option<&PreprocessorFunction>,
+    tokenizer: &Tokenizer,
+    checkpointing: &CheckpointConfig) {
 
     info!("starting read_xml_and_persist_env.");
     info!("reading files from directory {}.", input_dir);
@@ -22,7 +26,8 @@ pub fn read_xml_and_persist_env(
     let files = file_names_from_directory(input_dir)
         .expect("Unable to read file names from directory {}!");
 
-    let env = read_xmls_to_env(&files, tag, limit, preprocessor);
+    let (env, run) = read_xmls_to_env(&files, tag, limit, preprocessor,
+        tokenizer, checkpointing, output_dir);
 
     info!("done reading files from directory.");
     
@@ -37,11 +42,14 @@ pub fn read_xml_and_persist_env(
     env.serialize(output_dir.to_owned());
     info!("done writing binary files.");
 
+    run.finish(0);
+
     info!("done read_and_serialize_xmls.");
 
 }
 
-fn file_names_from_directory(dir: &str) -> std::io::Result<Vec<String>> {
+// files are sorted, so a resumed import sees them in the same order
+pub fn file_names_from_directory(dir: &str) -> std::io::Result<Vec<String>> {
     let mut r = Vec::new();
     for elem in read_dir(dir)? {
         let p = elem?.path();
@@ -49,34 +57,44 @@ fn file_names_from_directory(dir: &str) -> std::io::Result<Vec<String>> {
             r.push(p.to_str().unwrap().to_owned());
         }
     }
+    r.sort();
     Ok(r)
 }
 
-fn read_xmls_to_env (
+pub fn read_xmls_to_env<'a> (
     files: &[String], 
     tag: &[u8], 
     limit: Option<usize>, 
-    preprocessor: Option<&PreprocessorFunction>) -> Env {
+    preprocessor: Option<&PreprocessorFunction>,
+    tokenizer: &Tokenizer,
+    checkpointing: &'a CheckpointConfig,
+    output_dir: &'a str) -> (Env, ImportRun<'a>) {
 
-    let mut env = Env::new();
-    let mut count = 0usize;
+    let (mut env, mut run) = ImportRun::start(files, checkpointing, output_dir);
 
     for file_name in files {
-        count += process_xml_file(&file_name, tag,
-            &mut env, limit.map(|l| l - count), preprocessor);
+        if run.limit_reached(limit) { break; }
+
+        if ! run.start_file(file_name) { continue; }
 
-        if limit.is_some() && count >= limit.unwrap() { break; }
+        if process_xml_file(file_name, tag, &mut env, limit,
+            preprocessor, tokenizer, &mut run) {
+            run.finish_file();
+        }
     }
 
-    env
+    (env, run)
 }
 
+// returns true if the file was read to the end
 fn process_xml_file(
     file_name: &str, 
     tag: &[u8], 
     env: &mut Env, 
     limit: Option<usize>,
-    preprocessor: Option<&PreprocessorFunction>) -> usize {
+    preprocessor: Option<&PreprocessorFunction>,
+    tokenizer: &Tokenizer,
+    run: &mut ImportRun) -> bool {
 
     let mut reader = Reader::from_file(file_name)
         .expect("Could not read from input file.");
@@ -86,55 +104,46 @@ fn process_xml_file(
     let mut read: bool = false;
 
     let mut curr_str = String::new();
-    let mut count = 0usize;
 
-    info!("Start reading file {}", file_name);
+    // documents processed by a former run are skipped
+    let skip = run.checkpoint.docs_to_skip(file_name);
+    let mut file_count = 0usize;
+
+    if skip > 0 {
+        info!("Start reading file {}, skipping {} documents", file_name, skip);
+    } else {
+        info!("Start reading file {}", file_name);
+    }
 
     loop {
         match reader.read_event(&mut buf) {
 
-            Ok(Event::Start(ref e)) => {
-                if tag == e.name() {
-                    count+=1;
-                    if count % 1000000 == 0 {
-                        info!("count: {}", count);
-                    }
-                    if limit.is_some() && count >= limit.unwrap() {
-                        break;
+            Ok(Event::Start(ref e)) if tag == e.name() => {
+                file_count += 1;
+                if file_count > skip {
+                    if run.limit_reached(limit) {
+                        info!("document limit reached.");
+                        return false;
                     }
                     read = true;
                 }
             },
 
-            Ok(Event::End(ref e)) => {
-                if tag == e.name() {
-
-                    // optional preprocessor
-                    curr_str = if let Some(p_fn) = preprocessor {
-                        p_fn(&curr_str)
-                    } else { curr_str };
-
-                    let mut sentences = curr_str.unicode_sentences()
-                        .map(|sent| sent
-                             .split_word_bounds()
-                             .filter(|word| *word != " ")
-                             .map(|word| env.add_word(word))
-                             .collect::<Vec<u32>>())
-                        .collect::<Vec<Vec<u32>>>();
-
-                    for (i, sent) in sentences.iter().enumerate() {
-                        let sentence_id: SentenceId =
-                            (i + env.sentences.sentences.len()) as u32; 
-                        for word in sent {
-                            env.add_inv_idx(*word, sentence_id);
-                        }
-                    }
+            Ok(Event::End(ref e)) if tag == e.name() && read => {
 
-                    env.sentences.sentences.append(&mut sentences);
+                // optional preprocessor
+                curr_str = if let Some(p_fn) = preprocessor {
+                    p_fn(&curr_str)
+                } else { curr_str };
 
-                    curr_str = String::new(); 
-                    read = false;
-                }
+                add_document_to_env(
+                    &format!("{}#{}", file_name, file_count), "",
+                    &curr_str, env, tokenizer);
+
+                curr_str = String::new(); 
+                read = false;
+
+                run.document_done(env, reader.buffer_position() as u64);
             }
            
             Ok(Event::Text(ref e)) if read => {
@@ -154,5 +163,42 @@ fn process_xml_file(
     }
 
     info!("done reading file.");
-    count
+    true
+}
+
+// adds the text as a new document, see add_text_to_env
+pub fn add_document_to_env(id: &str, title: &str, text: &str,
+    env: &mut Env, tokenizer: &Tokenizer) {
+
+    let first_sentence = env.sentences.sentences.len() as SentenceId;
+
+    add_text_to_env(text, env, tokenizer);
+
+    env.documents.documents.push(Document {
+        id: id.to_owned(),
+        title: title.to_owned(),
+        first_sentence,
+        sentence_count: env.sentences.sentences.len() as u32 - first_sentence
+    });
+}
+
+// splits text into sentences and words, adds the words to the
+// dictionary and the sentences to the inverted index
+pub fn add_text_to_env(text: &str, env: &mut Env, tokenizer: &Tokenizer) {
+
+    let mut sentences = text.unicode_sentences()
+        .map(|sent| tokenizer.tokenize(sent).iter()
+             .map(|word| env.add_word(word))
+             .collect::<Vec<u32>>())
+        .collect::<Vec<Vec<u32>>>();
+
+    for (i, sent) in sentences.iter().enumerate() {
+        let sentence_id: SentenceId =
+            (i + env.sentences.sentences.len()) as u32; 
+        for word in sent {
+            env.add_inv_idx(*word, sentence_id);
+        }
+    }
+
+    env.sentences.sentences.append(&mut sentences);
 }